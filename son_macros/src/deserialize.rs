@@ -1,11 +1,12 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Fields, GenericArgument, PathArguments, Type, parse_macro_input};
+use syn::{Data, DeriveInput, Fields, GenericArgument, PathArguments, Type, ext::IdentExt, parse_macro_input};
 
 pub fn deserialize_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
+    let mut raw_paths_impl = quote! {};
     let from_son_impl = match &input.data {
         Data::Struct(data) => {
             let fields = match &data.fields {
@@ -15,10 +16,19 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
 
             let field_deserializers = fields.iter().map(|f| {
                 let field_name = f.ident.as_ref().unwrap();
-                let field_name_str = field_name.to_string();
+                let field_name_str = field_name.unraw().to_string();
                 let field_type = &f.ty;
                 let conversion_logic = generate_conversion_logic(field_type, quote!(field_value));
 
+                // Missing optional fields are treated like `null`.
+                if is_option(field_type) {
+                    return quote! {
+                        let #field_name = {
+                            let field_value = map.remove(#field_name_str).unwrap_or(Value::Null);
+                            #conversion_logic?
+                        };
+                    };
+                }
                 quote! {
                     let #field_name = {
                         let field_value = map.remove(#field_name_str)
@@ -30,6 +40,24 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
 
             let field_names = fields.iter().map(|f| f.ident.as_ref().unwrap());
 
+            // Fields that may contain `RawSon` values, directly or in a nested struct.
+            let raw_field_paths = fields.iter().filter_map(|f| {
+                let field_name_str = f.ident.as_ref().unwrap().unraw().to_string();
+                let field_type = raw_path_type(&f.ty)?;
+                return Some(quote! {
+                    for path in <#field_type as FromSon>::raw_paths() {
+                        paths.push(::son::Path::root().key(#field_name_str).join(&path));
+                    }
+                });
+            });
+            raw_paths_impl = quote! {
+                fn raw_paths() -> Vec<::son::Path> {
+                    let mut paths = Vec::new();
+                    #(#raw_field_paths)*
+                    return paths;
+                }
+            };
+
             quote! {
                 let mut map = if let Value::Object(map) = son {
                     map
@@ -50,7 +78,7 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
         Data::Enum(data) => {
             let deserialize_arms = data.variants.iter().map(|v| {
                 let variant_ident = &v.ident;
-                let variant_name_str = variant_ident.to_string();
+                let variant_name_str = variant_ident.unraw().to_string();
 
                 match &v.fields {
                     Fields::Unit => {
@@ -66,9 +94,14 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
 
             quote! {
                 if let Value::Enum(s) = son {
-                    match s.as_str() {
+                    // A variant qualified with another enum's name is rejected even if the names match.
+                    let unknown = || DeserializationError::UnknownVariant { variant: s.to_string(), enum_name: stringify!(#name).to_string() };
+                    if s.type_name().is_some_and(|type_name| type_name != stringify!(#name)) {
+                        return Err(unknown());
+                    }
+                    match s.name() {
                         #(#deserialize_arms,)*
-                        _ => Err(DeserializationError::UnknownVariant { variant: s.to_string(), enum_name: stringify!(#name).to_string() })
+                        _ => Err(unknown())
                     }
                 } else {
                     Err(DeserializationError::UnexpectedType {
@@ -86,6 +119,8 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
             fn from_son(son: Value) -> Result<Self, DeserializationError> {
                 #from_son_impl
             }
+
+            #raw_paths_impl
         }
 
         impl Deserialize for #name {}
@@ -94,6 +129,35 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+fn is_option(ty: &Type) -> bool {
+    return match ty {
+        Type::Path(type_path) => type_path.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    };
+}
+
+// The type whose `raw_paths` apply to a field, looking through `Option`. Primitives and `Vec`s never have any, and
+// skipping them also stops recursive types such as `children: Vec<Self>` from recursing forever.
+fn raw_path_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident == "Option" {
+        if let PathArguments::AngleBracketed(args) = &segment.arguments
+            && let Some(GenericArgument::Type(inner_ty)) = args.args.first()
+        {
+            return raw_path_type(inner_ty);
+        }
+        return None;
+    }
+    return match segment.ident.to_string().as_str() {
+        "String" | "char" | "bool" | "f32" | "f64" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "usize" | "Vec" => None,
+        _ => Some(ty),
+    };
+}
+
 /// A helper function to generate the token stream for converting a Value
 /// into a specific Rust type. (This function is unchanged)
 fn generate_conversion_logic(ty: &Type, value_accessor: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
@@ -136,16 +200,30 @@ fn generate_conversion_logic(ty: &Type, value_accessor: proc_macro2::TokenStream
         "bool" => quote! {
             if let Value::Bool(v) = #value_accessor { Ok(v) } else { Err(DeserializationError::UnexpectedType { expected: "Bool".to_string(), found: #value_accessor.get_type().to_string() }) }
         },
+        // Exact numbers are converted by the `FromSon` implementations, which check that the value fits.
         "f32" | "f64" => quote! {
-            if let Value::Float(v) = #value_accessor { Ok(v as #ty) } else { Err(DeserializationError::UnexpectedType { expected: "Float".to_string(), found: #value_accessor.get_type().to_string() }) }
+            match #value_accessor {
+                Value::Float(v) => Ok(v as #ty),
+                Value::Integer(v) => Ok(v as #ty),
+                Value::Number(n) => <#ty as FromSon>::from_son(Value::Number(n)),
+                v => Err(DeserializationError::UnexpectedType { expected: "Float".to_string(), found: v.get_type().to_string() }),
+            }
         },
         "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => quote! {
-            if let Value::Integer(v) = #value_accessor { Ok(v as #ty) } else { Err(DeserializationError::UnexpectedType { expected: "Integer".to_string(), found: #value_accessor.get_type().to_string() }) }
+            match #value_accessor {
+                Value::Integer(v) => Ok(v as #ty),
+                Value::Number(n) => <#ty as FromSon>::from_son(Value::Number(n)),
+                v => Err(DeserializationError::UnexpectedType { expected: "Integer".to_string(), found: v.get_type().to_string() }),
+            }
         },
-        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => quote! {
-            if let Value::Integer(v) = #value_accessor {
-                if v < 0 { Err(DeserializationError::InvalidValue{ message: "Cannot assign a negative integer to an unsigned type".to_string() }) } else { Ok(v as #ty) }
-            } else { Err(DeserializationError::UnexpectedType { expected: "Integer".to_string(), found: #value_accessor.get_type().to_string() }) }
+        "u8" | "u16" | "u32" | "u64" | "usize" => quote! {
+            match #value_accessor {
+                Value::Integer(v) => {
+                    if v < 0 { Err(DeserializationError::InvalidValue{ message: "Cannot assign a negative integer to an unsigned type".to_string() }) } else { Ok(v as #ty) }
+                }
+                Value::Number(n) => <#ty as FromSon>::from_son(Value::Number(n)),
+                v => Err(DeserializationError::UnexpectedType { expected: "Integer".to_string(), found: v.get_type().to_string() }),
+            }
         },
         "Vec" => {
             let inner_ty = if let Type::Path(type_path) = ty {
@@ -164,13 +242,14 @@ fn generate_conversion_logic(ty: &Type, value_accessor: proc_macro2::TokenStream
 
             if let Some(inner_ty) = inner_ty {
                 let inner_conversion = generate_conversion_logic(inner_ty, quote!(item));
+                // Byte strings are only valid for element types that accept them, such as `Vec<u8>`.
                 quote! {
-                    if let Value::Array(arr) = #value_accessor {
-                        arr.into_iter()
+                    match #value_accessor {
+                        Value::Array(arr) => arr.into_iter()
                            .map(|item| #inner_conversion)
-                           .collect::<Result<Vec<_>, _>>()
-                    } else {
-                        Err(DeserializationError::UnexpectedType { expected: "Array".to_string(), found: #value_accessor.get_type().to_string() })
+                           .collect::<Result<Vec<_>, _>>(),
+                        Value::Bytes(b) => <#ty as FromSon>::from_son(Value::Bytes(b)),
+                        v => Err(DeserializationError::UnexpectedType { expected: "Array".to_string(), found: v.get_type().to_string() }),
                     }
                 }
             } else {
@@ -178,7 +257,7 @@ fn generate_conversion_logic(ty: &Type, value_accessor: proc_macro2::TokenStream
             }
         }
         _ => quote! {
-            #ty::from_son(#value_accessor).map_err(|e| e.into())
+            <#ty as FromSon>::from_son(#value_accessor).map_err(|e| e.into())
         },
     };
 }
@@ -2,6 +2,7 @@ use proc_macro::TokenStream;
 
 mod deserialize;
 mod serialize;
+mod son;
 
 #[proc_macro_derive(Deserialize)]
 pub fn deserialize_derive(input: TokenStream) -> TokenStream {
@@ -12,3 +13,8 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
 pub fn serialize_derive(input: TokenStream) -> TokenStream {
     return serialize::serialize_derive(input);
 }
+
+#[proc_macro]
+pub fn son(input: TokenStream) -> TokenStream {
+    return son::son(input);
+}
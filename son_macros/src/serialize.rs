@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Fields, parse_macro_input};
+use syn::{Data, DeriveInput, Fields, ext::IdentExt, parse_macro_input};
 
 pub fn serialize_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -17,7 +17,7 @@ pub fn serialize_derive(input: TokenStream) -> TokenStream {
             // Generate the code to insert each field into the HashMap
             let field_serializers = fields.iter().map(|f| {
                 let field_name = f.ident.as_ref().unwrap();
-                let field_name_str = field_name.to_string();
+                let field_name_str = field_name.unraw().to_string();
                 quote! {
                     map.insert(#field_name_str.to_string(), self.#field_name.to_son());
                 }
@@ -33,14 +33,14 @@ pub fn serialize_derive(input: TokenStream) -> TokenStream {
             // Generate match arms for each enum variant
             let serialize_arms = data.variants.iter().map(|v| {
                 let variant_ident = &v.ident;
-                let variant_name_str = variant_ident.to_string();
+                let variant_name_str = variant_ident.unraw().to_string();
 
                 // For now, we only support simple "unit" variants (e.g., `MyEnum::Variant`).
                 // Variants with data like `MyEnum::Variant(i32)` are not yet supported.
                 match &v.fields {
                     Fields::Unit => {
                         quote! {
-                            Self::#variant_ident => Value::Enum(#variant_name_str.to_string())
+                            Self::#variant_ident => Value::Enum(::son::EnumVariant::qualified(stringify!(#name), #variant_name_str))
                         }
                     }
                     _ => {
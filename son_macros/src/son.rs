@@ -0,0 +1,226 @@
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{
+    Ident, Lit, LitStr, Token, braced, bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_macro_input, token,
+};
+
+// A SON value written inside `son!`, with `#expr` interpolating any Rust expression implementing `ToSon`.
+enum SonNode {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    // Decimal digits of an integer outside of the i128 range.
+    BigInteger(String),
+    Float(f64),
+    String(String),
+    Char(char),
+    // The optional qualifier, then the variant name.
+    Enum(Option<String>, String),
+    Bytes(Vec<u8>),
+    Array(Vec<SonNode>),
+    Object(Vec<(String, SonNode)>),
+    Interpolated(TokenStream2),
+}
+
+pub fn son(input: TokenStream) -> TokenStream {
+    let node = parse_macro_input!(input as SonRoot);
+    return TokenStream::from(node.0.into_token_stream());
+}
+
+struct SonRoot(SonNode);
+
+impl Parse for SonRoot {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `son!{ key: value }` is a shorthand for `son!({ key: value })`.
+        let is_field = (input.peek(Ident::peek_any) || input.peek(LitStr))
+            && ((input.peek2(Token![:]) && !input.peek2(Token![::]))
+                || input.peek2(token::Brace)
+                || input.peek2(token::Bracket));
+        if input.is_empty() || is_field {
+            return Ok(SonRoot(parse_fields(input)?));
+        }
+
+        let node = parse_value(input)?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after SON value"));
+        }
+        return Ok(SonRoot(node));
+    }
+}
+
+fn parse_value(input: ParseStream) -> syn::Result<SonNode> {
+    if input.peek(Token![#]) {
+        return parse_interpolation(input);
+    }
+    if input.peek(token::Brace) {
+        let content;
+        braced!(content in input);
+        return parse_fields(&content);
+    }
+    if input.peek(token::Bracket) {
+        let content;
+        bracketed!(content in input);
+        let mut values = Vec::new();
+        while !content.is_empty() {
+            values.push(parse_value(&content)?);
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        return Ok(SonNode::Array(values));
+    }
+    if input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        if input.peek(Ident) && input.fork().parse::<Ident>()? == "inf" {
+            input.parse::<Ident>()?;
+            return Ok(SonNode::Float(f64::NEG_INFINITY));
+        }
+        return match input.parse::<Lit>()? {
+            Lit::Int(lit) => Ok(integer_node(format!("-{}", lit.base10_digits()))),
+            Lit::Float(lit) => Ok(SonNode::Float(-lit.base10_parse::<f64>()?)),
+            lit => Err(syn::Error::new(lit.span(), "only numbers can be negated")),
+        };
+    }
+    if input.peek(Lit) {
+        return match input.parse::<Lit>()? {
+            Lit::Str(lit) => Ok(SonNode::String(lit.value())),
+            Lit::Char(lit) => Ok(SonNode::Char(lit.value())),
+            Lit::Int(lit) => Ok(integer_node(lit.base10_digits().to_string())),
+            Lit::Float(lit) => Ok(SonNode::Float(lit.base10_parse::<f64>()?)),
+            Lit::Bool(lit) => Ok(SonNode::Bool(lit.value)),
+            Lit::ByteStr(lit) => Ok(SonNode::Bytes(lit.value())),
+            lit => Err(syn::Error::new(lit.span(), "unsupported literal in SON value")),
+        };
+    }
+    if input.peek(Ident::peek_any) {
+        let ident = Ident::parse_any(input)?;
+        // Qualified variants are written `Status.Active` or `module::Status::Active`, like in SON files.
+        let mut segments = vec![ident.to_string()];
+        if input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            segments.push(Ident::parse_any(input)?.to_string());
+        } else {
+            while input.peek(Token![::]) {
+                input.parse::<Token![::]>()?;
+                segments.push(Ident::parse_any(input)?.to_string());
+            }
+        }
+        let name = segments.pop().unwrap();
+        // Like in SON files, keywords are only variant names when qualified.
+        return match name.as_str() {
+            "null" if segments.is_empty() => Ok(SonNode::Null),
+            "inf" if segments.is_empty() => Ok(SonNode::Float(f64::INFINITY)),
+            "NaN" if segments.is_empty() => Ok(SonNode::Float(f64::NAN)),
+            _ if segments.is_empty() => Ok(SonNode::Enum(None, name)),
+            _ => Ok(SonNode::Enum(Some(segments.join("::")), name)),
+        };
+    }
+    return Err(input.error("expected a SON value"));
+}
+
+fn integer_node(digits: String) -> SonNode {
+    return match digits.parse::<i128>() {
+        Ok(i) => SonNode::Integer(i),
+        Err(_) => SonNode::BigInteger(digits),
+    };
+}
+
+fn parse_fields(input: ParseStream) -> syn::Result<SonNode> {
+    let mut fields: Vec<(String, SonNode)> = Vec::new();
+    while !input.is_empty() {
+        let (key, span) = if input.peek(LitStr) {
+            let lit = input.parse::<LitStr>()?;
+            (lit.value(), lit.span())
+        } else if input.peek(Ident::peek_any) {
+            let ident = Ident::parse_any(input)?;
+            (ident.to_string(), ident.span())
+        } else {
+            return Err(input.error("expected a field name"));
+        };
+
+        if fields.iter().any(|(k, _)| *k == key) {
+            return Err(syn::Error::new(span, format!("duplicate field `{}`", key)));
+        }
+
+        // Like in SON files, the colon is optional before objects and arrays.
+        if !input.peek(token::Brace) && !input.peek(token::Bracket) {
+            input.parse::<Token![:]>()?;
+        }
+        fields.push((key, parse_value(input)?));
+
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    return Ok(SonNode::Object(fields));
+}
+
+// Parses `#ident`, `#ident.field.other` or `#(any expression)`.
+fn parse_interpolation(input: ParseStream) -> syn::Result<SonNode> {
+    let hash = input.parse::<Token![#]>()?;
+    if input.peek(token::Paren) {
+        let group = input.parse::<TokenTree>()?;
+        return Ok(SonNode::Interpolated(group.into_token_stream()));
+    }
+    if !input.peek(Ident::peek_any) {
+        return Err(syn::Error::new(
+            hash.span,
+            "expected an identifier or a parenthesized expression after `#`",
+        ));
+    }
+
+    let mut tokens = Ident::parse_any(input)?.into_token_stream();
+    while input.peek(Token![.]) && !input.peek(Token![..]) {
+        tokens.extend(input.parse::<Token![.]>()?.into_token_stream());
+        tokens.extend(input.parse::<TokenTree>()?.into_token_stream());
+    }
+    return Ok(SonNode::Interpolated(tokens));
+}
+
+impl ToTokens for SonNode {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let expanded = match self {
+            SonNode::Null => quote! { ::son::Value::Null },
+            SonNode::Bool(b) => quote! { ::son::Value::Bool(#b) },
+            SonNode::Integer(i) => quote! { ::son::Value::Integer(#i) },
+            SonNode::BigInteger(digits) => quote! {
+                ::son::Value::from(#digits.parse::<::son::BigInt>().unwrap())
+            },
+            // Literal tokens can't hold `inf` or `NaN`.
+            SonNode::Float(f) if !f.is_finite() => {
+                let bits = f.to_bits();
+                quote! { ::son::Value::Float(::std::primitive::f64::from_bits(#bits)) }
+            }
+            SonNode::Float(f) => quote! { ::son::Value::Float(#f) },
+            SonNode::String(s) => quote! { ::son::Value::String(::std::string::String::from(#s)) },
+            SonNode::Char(c) => quote! { ::son::Value::Char(#c) },
+            SonNode::Enum(None, name) => quote! { ::son::Value::Enum(::son::EnumVariant::new(#name)) },
+            SonNode::Enum(Some(qualifier), name) => {
+                quote! { ::son::Value::Enum(::son::EnumVariant::qualified(#qualifier, #name)) }
+            }
+            SonNode::Bytes(b) => quote! { ::son::Value::Bytes(::std::vec![#(#b),*]) },
+            SonNode::Array(values) => quote! { ::son::Value::Array(::std::vec![#(#values),*]) },
+            SonNode::Object(fields) if fields.is_empty() => {
+                quote! { ::son::Value::Object(::std::collections::HashMap::new()) }
+            }
+            SonNode::Object(fields) => {
+                let inserts = fields.iter().map(|(key, value)| {
+                    quote! { __son_map.insert(::std::string::String::from(#key), #value); }
+                });
+                quote! {
+                    ::son::Value::Object({
+                        let mut __son_map = ::std::collections::HashMap::new();
+                        #(#inserts)*
+                        __son_map
+                    })
+                }
+            }
+            SonNode::Interpolated(expr) => quote! { ::son::ToSon::to_son(&(#expr)) },
+        };
+        tokens.extend(expanded);
+    }
+}
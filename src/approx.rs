@@ -0,0 +1,155 @@
+use crate::{MismatchError, Path, Value};
+use std::collections::BTreeSet;
+
+// How far apart two floats may be and still compare as equal in `Value::approx_eq`.
+//
+// Floats are equal if they are within `abs` of each other, within `rel` times the larger magnitude, or at most `ulps`
+// representable floats apart. Infinities only equal themselves, and `NaN` equals `NaN` as with `==`. The default
+// tolerance is zero, which compares floats exactly.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tolerance {
+    pub abs: f64,
+    pub rel: f64,
+    pub ulps: u64,
+    pub integers_as_floats: bool,
+}
+
+impl Tolerance {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn abs(mut self, abs: f64) -> Self {
+        self.abs = abs;
+        return self;
+    }
+
+    pub fn rel(mut self, rel: f64) -> Self {
+        self.rel = rel;
+        return self;
+    }
+
+    pub fn ulps(mut self, ulps: u64) -> Self {
+        self.ulps = ulps;
+        return self;
+    }
+
+    // When enabled, integers are compared with floats and exact numbers by value, so `1` equals `1.0`.
+    pub fn integers_as_floats(mut self, integers_as_floats: bool) -> Self {
+        self.integers_as_floats = integers_as_floats;
+        return self;
+    }
+
+    fn floats_equal(&self, a: f64, b: f64) -> bool {
+        if a == b || (a.is_nan() && b.is_nan()) {
+            return true;
+        }
+        if !a.is_finite() || !b.is_finite() {
+            return false;
+        }
+        let difference = (a - b).abs();
+        if difference <= self.abs || difference <= self.rel * a.abs().max(b.abs()) {
+            return true;
+        }
+        return ulps_between(a, b) <= self.ulps as u128;
+    }
+}
+
+// Maps floats onto integers in the same order, so adjacent floats are adjacent integers and `-0.0` meets `0.0`.
+fn ulps_between(a: f64, b: f64) -> u128 {
+    let ordered = |f: f64| {
+        let bits = f.to_bits() as i64;
+        return if bits < 0 { i64::MIN - bits } else { bits };
+    };
+    return (ordered(a) as i128 - ordered(b) as i128).unsigned_abs();
+}
+
+// Numbers that are compared with a tolerance.
+fn approximate(value: &Value) -> Option<f64> {
+    return match value {
+        Value::Float(f) => Some(*f),
+        Value::Number(n) => Some(n.to_f64()),
+        _ => None,
+    };
+}
+
+fn integer(value: &Value) -> Option<f64> {
+    return match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::BigInteger(b) => Some(b.to_f64()),
+        _ => None,
+    };
+}
+
+impl Value {
+    // Compares two values structurally, allowing floats to differ by `tolerance`. Objects are compared key by key in
+    // sorted order, and on a mismatch the error holds the path of the first difference.
+    pub fn approx_eq(&self, other: &Value, tolerance: Tolerance) -> Result<(), MismatchError> {
+        return approx_eq_inner(self, other, &Path::root(), &tolerance);
+    }
+}
+
+fn approx_eq_inner(a: &Value, b: &Value, path: &Path, tolerance: &Tolerance) -> Result<(), MismatchError> {
+    let mismatch = |message: String| {
+        return Err(MismatchError {
+            path: path.clone(),
+            message,
+        });
+    };
+    let values_differ = || mismatch(format!("{} != {}", a.to_canonical_string(), b.to_canonical_string()));
+
+    match (a, b) {
+        (Value::Raw(raw), other) | (other, Value::Raw(raw)) => {
+            let Ok(parsed) = raw.parse::<Value>() else {
+                return if a == b { Ok(()) } else { values_differ() };
+            };
+            return match a {
+                Value::Raw(_) => approx_eq_inner(&parsed, other, path, tolerance),
+                _ => approx_eq_inner(other, &parsed, path, tolerance),
+            };
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+                approx_eq_inner(a, b, &path.index(i), tolerance)?;
+            }
+            if a.len() != b.len() {
+                let side = if a.len() < b.len() { "left" } else { "right" };
+                return Err(MismatchError {
+                    path: path.index(a.len().min(b.len())),
+                    message: format!("missing from the {} array", side),
+                });
+            }
+            return Ok(());
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let key_path = path.key(key);
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => approx_eq_inner(a, b, &key_path, tolerance)?,
+                    (a, _) => {
+                        let side = if a.is_none() { "left" } else { "right" };
+                        return Err(MismatchError {
+                            path: key_path,
+                            message: format!("missing from the {} object", side),
+                        });
+                    }
+                }
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let numbers = match (approximate(a), approximate(b)) {
+        (Some(x), Some(y)) => Some((x, y)),
+        (Some(x), None) if tolerance.integers_as_floats => integer(b).map(|y| (x, y)),
+        (None, Some(y)) if tolerance.integers_as_floats => integer(a).map(|x| (x, y)),
+        _ => None,
+    };
+    let equal = match numbers {
+        Some((x, y)) => tolerance.floats_equal(x, y),
+        None => a == b,
+    };
+    return if equal { Ok(()) } else { values_differ() };
+}
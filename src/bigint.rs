@@ -0,0 +1,208 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    ops::Neg,
+    str::FromStr,
+};
+
+// An arbitrary-size integer, used by `Value::BigInteger` for numbers that don't fit in an `i128`.
+// Only the operations needed to read, compare, convert and print numbers are implemented.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    // Base 2^32 limbs, least significant first, without trailing zeros. Zero has no limbs and is never negative.
+    magnitude: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "invalid digit found in string");
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl BigInt {
+    // Bytes allocated for the limbs, see `Value::heap_size`.
+    pub(crate) fn heap_size(&self) -> usize {
+        return self.magnitude.capacity() * size_of::<u32>();
+    }
+
+    pub fn zero() -> Self {
+        return Self::default();
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return self.magnitude.is_empty();
+    }
+
+    pub fn is_negative(&self) -> bool {
+        return self.negative;
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude_u128()?;
+        if !self.negative {
+            return i128::try_from(magnitude).ok();
+        }
+        return match magnitude.cmp(&(i128::MIN.unsigned_abs())) {
+            Ordering::Less => Some(-(magnitude as i128)),
+            Ordering::Equal => Some(i128::MIN),
+            Ordering::Greater => None,
+        };
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative {
+            return None;
+        }
+        return self.magnitude_u128();
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0.0, |acc, limb| acc * 4294967296.0 + *limb as f64);
+        return if self.negative { -magnitude } else { magnitude };
+    }
+
+    fn magnitude_u128(&self) -> Option<u128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        return Some(
+            self.magnitude
+                .iter()
+                .rev()
+                .fold(0u128, |acc, limb| (acc << 32) | *limb as u128),
+        );
+    }
+
+    fn from_magnitude(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        return Self { negative, magnitude };
+    }
+
+    // magnitude = magnitude * multiplier + addend
+    fn multiply_add(&mut self, multiplier: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.magnitude.iter_mut() {
+            let product = *limb as u64 * multiplier as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.magnitude.push(carry as u32);
+        }
+    }
+
+    // magnitude = magnitude / divisor, returns the remainder
+    fn divide(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.magnitude.iter_mut().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        while self.magnitude.last() == Some(&0) {
+            self.magnitude.pop();
+        }
+        return remainder as u32;
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    // Parses an optionally negative sequence of decimal digits of any length.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut result = BigInt::zero();
+        // Nine decimal digits always fit in a u32.
+        for chunk in digits.as_bytes().chunks(9) {
+            let value = chunk.iter().fold(0u32, |acc, digit| acc * 10 + (digit - b'0') as u32);
+            result.multiply_add(10u32.pow(chunk.len() as u32), value);
+        }
+        return Ok(Self::from_magnitude(negative, result.magnitude));
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut chunks = Vec::new();
+        let mut remaining = self.clone();
+        while !remaining.is_zero() {
+            chunks.push(remaining.divide(1_000_000_000));
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{:09}", chunk)?;
+        }
+        return Ok(());
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(value: u128) -> Self {
+        let magnitude = (0..4).map(|i| (value >> (32 * i)) as u32).collect();
+        return Self::from_magnitude(false, magnitude);
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let magnitude = BigInt::from(value.unsigned_abs()).magnitude;
+        return Self::from_magnitude(value < 0, magnitude);
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        return Self::from_magnitude(!self.negative, self.magnitude);
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude_order = self.magnitude.len().cmp(&other.magnitude.len()).then_with(|| {
+            return self.magnitude.iter().rev().cmp(other.magnitude.iter().rev());
+        });
+        return match (self.negative, other.negative) {
+            (false, false) => magnitude_order,
+            (true, true) => magnitude_order.reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        };
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
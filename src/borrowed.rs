@@ -0,0 +1,215 @@
+use crate::{
+    BigInt, ByteSize, DateTime, EnumVariant, Number, ParseError, ParseOptions, RawSon, Value,
+    parser::{Grammar, ValueBuilder},
+    token::{Token, TokenType},
+    variant,
+};
+use std::{borrow::Cow, collections::HashMap, time::Duration};
+
+// A parsed document that borrows its strings, enum names and object keys from the input instead of allocating them.
+//
+// Strings are only allocated when they contain escape sequences that need decoding. Everything else is the same as
+// `Value`, and `into_owned` converts to a `Value` when the document needs to outlive its input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedValue<'a> {
+    Null,
+    Bool(bool),
+    Float(f64),
+    Integer(i128),
+    BigInteger(BigInt),
+    Number(Number),
+    String(Cow<'a, str>),
+    Char(char),
+    // The variant as written, including a `Status.` or `module::Status::` qualifier.
+    Enum(Cow<'a, str>),
+    Bytes(Vec<u8>),
+    DateTime(DateTime),
+    Duration(Duration),
+    ByteSize(ByteSize),
+    Raw(RawSon),
+    Array(Vec<BorrowedValue<'a>>),
+    Object(HashMap<Cow<'a, str>, BorrowedValue<'a>>),
+}
+
+impl<'a> BorrowedValue<'a> {
+    pub fn get_type(&self) -> String {
+        return match self {
+            BorrowedValue::Null => "Null".to_string(),
+            BorrowedValue::Bool(_) => "Bool".to_string(),
+            BorrowedValue::Float(_) => "Float".to_string(),
+            BorrowedValue::Integer(_) => "Integer".to_string(),
+            BorrowedValue::BigInteger(_) => "BigInteger".to_string(),
+            BorrowedValue::Number(_) => "Number".to_string(),
+            BorrowedValue::String(_) => "String".to_string(),
+            BorrowedValue::Char(_) => "Char".to_string(),
+            BorrowedValue::Enum(_) => "Enum".to_string(),
+            BorrowedValue::Bytes(_) => "Bytes".to_string(),
+            BorrowedValue::DateTime(_) => "DateTime".to_string(),
+            BorrowedValue::Duration(_) => "Duration".to_string(),
+            BorrowedValue::ByteSize(_) => "ByteSize".to_string(),
+            BorrowedValue::Raw(_) => "Raw".to_string(),
+            BorrowedValue::Array(_) => "Array".to_string(),
+            BorrowedValue::Object(_) => "Object".to_string(),
+        };
+    }
+
+    // Looks up a field of an object.
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'a>> {
+        return match self {
+            BorrowedValue::Object(o) => o.get(key),
+            _ => None,
+        };
+    }
+
+    // Looks up an item of an array.
+    pub fn get_index(&self, index: usize) -> Option<&BorrowedValue<'a>> {
+        return match self {
+            BorrowedValue::Array(a) => a.get(index),
+            _ => None,
+        };
+    }
+
+    // The text of a string or the name of an enum variant.
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            BorrowedValue::String(s) => Some(s),
+            BorrowedValue::Enum(e) => Some(variant::split_source(e).1),
+            _ => None,
+        };
+    }
+
+    pub fn into_owned(self) -> Value {
+        return match self {
+            BorrowedValue::Null => Value::Null,
+            BorrowedValue::Bool(b) => Value::Bool(b),
+            BorrowedValue::Float(f) => Value::Float(f),
+            BorrowedValue::Integer(i) => Value::Integer(i),
+            BorrowedValue::BigInteger(b) => Value::BigInteger(b),
+            BorrowedValue::Number(n) => Value::Number(n),
+            BorrowedValue::String(s) => Value::String(s.into_owned()),
+            BorrowedValue::Char(c) => Value::Char(c),
+            BorrowedValue::Enum(e) => Value::Enum(EnumVariant::from_source(&e)),
+            BorrowedValue::Bytes(b) => Value::Bytes(b),
+            BorrowedValue::DateTime(d) => Value::DateTime(d),
+            BorrowedValue::Duration(d) => Value::Duration(d),
+            BorrowedValue::ByteSize(s) => Value::ByteSize(s),
+            BorrowedValue::Raw(r) => Value::Raw(r),
+            BorrowedValue::Array(a) => Value::Array(a.into_iter().map(BorrowedValue::into_owned).collect()),
+            BorrowedValue::Object(o) => {
+                Value::Object(o.into_iter().map(|(k, v)| (k.into_owned(), v.into_owned())).collect())
+            }
+        };
+    }
+}
+
+impl From<Value> for BorrowedValue<'_> {
+    fn from(value: Value) -> Self {
+        return match value {
+            Value::Null => BorrowedValue::Null,
+            Value::Bool(b) => BorrowedValue::Bool(b),
+            Value::Float(f) => BorrowedValue::Float(f),
+            Value::Integer(i) => BorrowedValue::Integer(i),
+            Value::BigInteger(b) => BorrowedValue::BigInteger(b),
+            Value::Number(n) => BorrowedValue::Number(n),
+            Value::String(s) => BorrowedValue::String(Cow::Owned(s)),
+            Value::Char(c) => BorrowedValue::Char(c),
+            Value::Enum(e) => BorrowedValue::Enum(Cow::Owned(e.to_string())),
+            Value::Bytes(b) => BorrowedValue::Bytes(b),
+            Value::DateTime(d) => BorrowedValue::DateTime(d),
+            Value::Duration(d) => BorrowedValue::Duration(d),
+            Value::ByteSize(s) => BorrowedValue::ByteSize(s),
+            Value::Raw(r) => BorrowedValue::Raw(r),
+            Value::Array(a) => BorrowedValue::Array(a.into_iter().map(BorrowedValue::from).collect()),
+            Value::Object(o) => BorrowedValue::Object(o.into_iter().map(|(k, v)| (Cow::Owned(k), v.into())).collect()),
+        };
+    }
+}
+
+// Parses a string into a `BorrowedValue`, using the same grammar as `SonParser`.
+pub struct BorrowedParser<'a> {
+    grammar: Grammar<&'a [u8], BorrowedBuilder<'a>>,
+}
+
+impl<'a> BorrowedParser<'a> {
+    pub fn new(input: &'a str) -> BorrowedParser<'a> {
+        return Self::with_options(input, ParseOptions::default());
+    }
+
+    pub fn with_options(input: &'a str, options: ParseOptions) -> BorrowedParser<'a> {
+        return Self {
+            grammar: Grammar::new(input.as_bytes(), options, BorrowedBuilder::new(input)),
+        };
+    }
+
+    pub fn parse(&mut self) -> Result<BorrowedValue<'a>, ParseError> {
+        return self.grammar.parse();
+    }
+}
+
+// Builds `BorrowedValue`s that borrow from `input`.
+pub(crate) struct BorrowedBuilder<'a> {
+    input: &'a str,
+}
+
+impl<'a> BorrowedBuilder<'a> {
+    pub(crate) fn new(input: &'a str) -> BorrowedBuilder<'a> {
+        return BorrowedBuilder { input };
+    }
+
+    // The input text of a token starting at `offset`.
+    pub(crate) fn token_source(&self, token: &Token, offset: usize) -> &'a str {
+        return &self.input[offset..offset + token.get_source().len()];
+    }
+
+    pub(crate) fn string_literal(&self, token: &Token, offset: usize) -> Cow<'a, str> {
+        let source = self.token_source(token, offset);
+        let contents = &source[1..source.len() - 1];
+        if !contents.contains('\\') {
+            return Cow::Borrowed(contents);
+        }
+        return match token.get_value() {
+            Some(Value::String(s)) => Cow::Owned(s),
+            _ => unreachable!("string literals always have a value"),
+        };
+    }
+}
+
+impl<'a> ValueBuilder for BorrowedBuilder<'a> {
+    type Value = BorrowedValue<'a>;
+    type Key = Cow<'a, str>;
+
+    fn scalar(&mut self, value: Value) -> BorrowedValue<'a> {
+        return value.into();
+    }
+
+    fn string(&mut self, token: &Token, offset: usize) -> BorrowedValue<'a> {
+        return BorrowedValue::String(self.string_literal(token, offset));
+    }
+
+    fn enum_variant(&mut self, token: &Token, offset: usize) -> BorrowedValue<'a> {
+        return BorrowedValue::Enum(Cow::Borrowed(self.token_source(token, offset)));
+    }
+
+    fn key(&mut self, token: &Token, offset: usize) -> Cow<'a, str> {
+        return match token.get_type() {
+            TokenType::StringLiteral => self.string_literal(token, offset),
+            _ => Cow::Borrowed(self.token_source(token, offset)),
+        };
+    }
+
+    fn negate(&mut self, value: BorrowedValue<'a>) -> BorrowedValue<'a> {
+        return value.into_owned().negate().into();
+    }
+
+    fn is_null(&self, value: &BorrowedValue<'a>) -> bool {
+        return matches!(value, BorrowedValue::Null);
+    }
+
+    fn array(&mut self, items: Vec<BorrowedValue<'a>>) -> BorrowedValue<'a> {
+        return BorrowedValue::Array(items);
+    }
+
+    fn object(&mut self, fields: HashMap<Cow<'a, str>, BorrowedValue<'a>>) -> BorrowedValue<'a> {
+        return BorrowedValue::Object(fields);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::ops::{Deref, DerefMut};
+
+// Binary data serialized as a compact `Value::Bytes` instead of an array of integers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        return Self(bytes);
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        return self.0;
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        return Self(bytes);
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        return Self(bytes.to_vec());
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        return bytes.0;
+    }
+}
+
+impl Deref for Bytes {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl DerefMut for Bytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+impl Serialize for Bytes {}
+impl ToSon for Bytes {
+    fn to_son(&self) -> Value {
+        return Value::Bytes(self.0.clone());
+    }
+}
+
+impl Deserialize for Bytes {}
+impl FromSon for Bytes {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return Ok(Bytes(u8::vec_from_son(son)?));
+    }
+}
+
+// Literal encoding and decoding.
+//
+// Byte strings are written as `b"..."`, where printable ASCII is used as is and other bytes are escaped with
+// `\xHH`, `\n`, `\r`, `\t`, `\0`, `\\` or `\"`, or as `b64"..."` holding standard base64 with optional padding.
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let indices = [
+            b[0] >> 2,
+            ((b[0] & 0b11) << 4) | (b[1] >> 4),
+            ((b[1] & 0b1111) << 2) | (b[2] >> 6),
+            b[2] & 0b111111,
+        ];
+        for (i, index) in indices.iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[*index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    return encoded;
+}
+
+pub fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let trimmed = encoded.trim_end_matches('=');
+    if trimmed.len() % 4 == 1 {
+        return None;
+    }
+    // Padding is optional, but when present it has to complete the last group of four characters.
+    let padding = encoded.len() - trimmed.len();
+    if padding > 0 && padding != (4 - trimmed.len() % 4) % 4 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in trimmed.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    return Some(bytes);
+}
+
+pub fn escape_byte_string(bytes: &[u8]) -> String {
+    let mut escaped = String::with_capacity(bytes.len());
+    for byte in bytes {
+        match byte {
+            b'"' => escaped.push_str("\\\""),
+            b'\\' => escaped.push_str("\\\\"),
+            b'\n' => escaped.push_str("\\n"),
+            b'\r' => escaped.push_str("\\r"),
+            b'\t' => escaped.push_str("\\t"),
+            b'\0' => escaped.push_str("\\0"),
+            0x20..=0x7e => escaped.push(*byte as char),
+            _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    return escaped;
+}
+
+pub fn unescape_byte_string(escaped: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            if !c.is_ascii() {
+                return None;
+            }
+            bytes.push(c as u8);
+            continue;
+        }
+        match chars.next()? {
+            '"' => bytes.push(b'"'),
+            '\\' => bytes.push(b'\\'),
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            '0' => bytes.push(b'\0'),
+            'x' => {
+                let hex: String = [chars.next()?, chars.next()?].iter().collect();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    return Some(bytes);
+}
+
+// Decodes the full source of a byte string literal, including its `b` or `b64` prefix and quotes.
+pub fn decode_literal(source: &str) -> Option<Vec<u8>> {
+    if let Some(encoded) = source.strip_prefix("b64\"").and_then(|s| s.strip_suffix('"')) {
+        return decode_base64(encoded);
+    }
+    let escaped = source.strip_prefix("b\"")?.strip_suffix('"')?;
+    return unescape_byte_string(escaped);
+}
+
+// Mostly textual data is written as an escaped byte string, anything else as base64.
+pub fn encode_literal(bytes: &[u8]) -> String {
+    let is_text = bytes.iter().all(|b| matches!(b, 0x20..=0x7e | b'\n' | b'\r' | b'\t'));
+    if is_text {
+        return format!("b\"{}\"", escape_byte_string(bytes));
+    }
+    return format!("b64\"{}\"", encode_base64(bytes));
+}
@@ -0,0 +1,144 @@
+use crate::{Number, Value, bytes, lexer, printer, units};
+
+// A canonical text form of values, for using documents as cache keys or comparing them across machines.
+//
+// The output is a single line with object keys sorted by their UTF-8 bytes, `, ` between items and `: ` after keys,
+// and no comments. Numbers are normalized: floats use the shortest text that reads back as the same float, always with
+// a fractional part, `-0.0` is written as `0.0`, and exact numbers drop leading zeros and trailing fractional zeros.
+// Strings and chars escape `\`, quotes, `\n`, `\r`, `\t`, and other control characters as `\u{..}`. Keys that aren't
+// identifiers are written as quoted strings. Enum variants are written with their type name but not its module path,
+// as `Status.Active`. Raw values are parsed and written canonically as well.
+//
+// Equal values always have the same canonical form.
+pub fn to_canonical_string(value: &Value) -> String {
+    let mut output = String::new();
+    write_canonical(&mut output, value);
+    return output;
+}
+
+// A 64-bit FNV-1a hash of the canonical form, so it doesn't depend on formatting, comments or key order, and is stable
+// across platforms and versions of this crate.
+pub fn fingerprint(value: &Value) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for byte in to_canonical_string(value).bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    return hash;
+}
+
+fn write_canonical(output: &mut String, value: &Value) {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        Value::Float(f) => output.push_str(&canonical_float(*f)),
+        Value::Integer(i) => output.push_str(&i.to_string()),
+        Value::BigInteger(b) => output.push_str(&b.to_string()),
+        Value::Number(n) => output.push_str(&canonical_number(n)),
+        Value::String(s) => output.push_str(&printer::quote(s, '"')),
+        Value::Char(c) => output.push_str(&printer::quote(&c.to_string(), '\'')),
+        Value::Enum(e) => {
+            if let Some(type_name) = e.type_name() {
+                output.push_str(type_name);
+                output.push('.');
+            }
+            output.push_str(e.name());
+        }
+        Value::Bytes(b) => output.push_str(&bytes::encode_literal(b)),
+        Value::DateTime(d) => output.push_str(&d.to_string()),
+        Value::Duration(d) => output.push_str(&units::format_duration(d)),
+        Value::ByteSize(s) => output.push_str(&s.to_string()),
+        Value::Raw(r) => match r.parse::<Value>() {
+            Ok(value) => write_canonical(output, &value),
+            Err(_) => output.push_str(r.as_str()),
+        },
+        Value::Array(a) => {
+            output.push('[');
+            for (i, item) in a.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_canonical(output, item);
+            }
+            output.push(']');
+        }
+        Value::Object(o) => {
+            let mut entries: Vec<(&String, &Value)> = o.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            output.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                if lexer::is_identifier(key) {
+                    output.push_str(key);
+                } else {
+                    output.push_str(&printer::quote(key, '"'));
+                }
+                output.push_str(": ");
+                write_canonical(output, value);
+            }
+            output.push('}');
+        }
+    }
+}
+
+fn canonical_float(f: f64) -> String {
+    if f == 0.0 {
+        return "0.0".to_string();
+    }
+    if !f.is_finite() {
+        return f.to_string();
+    }
+    // `Display` writes the shortest digits that read back as the same float, without an exponent.
+    let text = f.to_string();
+    if text.contains('.') {
+        return text;
+    }
+    return format!("{}.0", text);
+}
+
+fn canonical_number(number: &Number) -> String {
+    let literal = number.as_str();
+    let (negative, unsigned) = match literal.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, literal),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction.trim_end_matches('0'))),
+        None => (unsigned, None),
+    };
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+
+    let mut text = String::new();
+    let is_zero = integer == "0" && fraction.is_none_or(|fraction| fraction.is_empty());
+    if negative && !is_zero {
+        text.push('-');
+    }
+    text.push_str(integer);
+    match fraction {
+        Some("") => text.push_str(".0"),
+        Some(fraction) => {
+            text.push('.');
+            text.push_str(fraction);
+        }
+        None => {}
+    }
+    return text;
+}
+
+impl Value {
+    pub fn to_canonical_string(&self) -> String {
+        return to_canonical_string(self);
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        return fingerprint(self);
+    }
+}
@@ -0,0 +1,496 @@
+use crate::{
+    DeserializationError, EnumVariant, Error, ParseOptions, Path, PathSegment, Value,
+    borrowed::BorrowedBuilder,
+    parser::{Grammar, ValueBuilder},
+    token::Token,
+    variant,
+};
+use std::{borrow::Cow, collections::HashMap};
+
+// A read-only document stored in a few flat buffers instead of a tree of `Value`s, for large data sets held in
+// memory.
+//
+// Object keys and enum names are interned once per document, strings of up to `INLINE_CAPACITY` bytes are stored
+// inline in their node, and longer ones share a single text buffer. Nodes, array items and object entries live in
+// arenas and refer to each other by index, so a document makes a handful of allocations no matter how many values it
+// holds. Values are read through `CompactValue`, which has the same accessors as `BorrowedValue`.
+//
+// Indices are `u32`s, so a document holds at most 4 GiB of long strings and 2^32 nodes. Larger documents are reported
+// as an error.
+#[derive(Debug, Clone)]
+pub struct CompactDocument {
+    nodes: Vec<Node>,
+    // Node indices of array items, each array using a contiguous range.
+    items: Vec<u32>,
+    // Symbol and node indices of object entries, each object using a contiguous range sorted by key.
+    entries: Vec<(u32, u32)>,
+    symbols: Vec<Box<str>>,
+    text: String,
+    // Values that are rare enough to be kept as they are: big numbers, bytes, dates, durations and sizes.
+    others: Vec<Value>,
+    root: u32,
+}
+
+const INLINE_CAPACITY: usize = 14;
+
+// 16 bytes, the same as a `f64` with its tag.
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Char(char),
+    InlineString { len: u8, bytes: [u8; INLINE_CAPACITY] },
+    String { start: u32, len: u32 },
+    Enum(u32),
+    Array { start: u32, len: u32 },
+    Object { start: u32, len: u32 },
+    Other(u32),
+}
+
+const _: () = assert!(size_of::<Node>() == 16);
+
+// Builds a document bottom-up, children before their parents.
+struct Builder {
+    document: CompactDocument,
+    interned: HashMap<Box<str>, u32>,
+    // Set when an index doesn't fit in a `u32`, making `finish` fail.
+    too_large: bool,
+}
+
+impl Builder {
+    fn new() -> Builder {
+        let document = CompactDocument {
+            nodes: Vec::new(),
+            items: Vec::new(),
+            entries: Vec::new(),
+            symbols: Vec::new(),
+            text: String::new(),
+            others: Vec::new(),
+            root: 0,
+        };
+        return Builder {
+            document,
+            interned: HashMap::new(),
+            too_large: false,
+        };
+    }
+
+    fn finish(mut self, root: u32) -> Result<CompactDocument, Error> {
+        if self.too_large {
+            return Err(Error::DeserializationError(DeserializationError::InvalidValue {
+                message: "the document is too large for a compact representation".to_string(),
+            }));
+        }
+        self.document.root = root;
+        self.document.nodes.shrink_to_fit();
+        self.document.items.shrink_to_fit();
+        self.document.entries.shrink_to_fit();
+        self.document.symbols.shrink_to_fit();
+        self.document.text.shrink_to_fit();
+        self.document.others.shrink_to_fit();
+        return Ok(self.document);
+    }
+
+    fn index(&mut self, len: usize) -> u32 {
+        return match u32::try_from(len) {
+            Ok(index) => index,
+            Err(_) => {
+                self.too_large = true;
+                0
+            }
+        };
+    }
+
+    fn push(&mut self, node: Node) -> u32 {
+        self.document.nodes.push(node);
+        return self.index(self.document.nodes.len() - 1);
+    }
+
+    fn intern(&mut self, symbol: &str) -> u32 {
+        if let Some(id) = self.interned.get(symbol) {
+            return *id;
+        }
+        let id = self.index(self.document.symbols.len());
+        self.document.symbols.push(symbol.into());
+        self.interned.insert(symbol.into(), id);
+        return id;
+    }
+
+    fn push_string(&mut self, string: &str) -> u32 {
+        if string.len() <= INLINE_CAPACITY {
+            let mut bytes = [0; INLINE_CAPACITY];
+            bytes[..string.len()].copy_from_slice(string.as_bytes());
+            return self.push(Node::InlineString {
+                len: string.len() as u8,
+                bytes,
+            });
+        }
+        let start = self.index(self.document.text.len());
+        self.document.text.push_str(string);
+        let len = self.index(string.len());
+        return self.push(Node::String { start, len });
+    }
+
+    fn push_array(&mut self, items: Vec<u32>) -> u32 {
+        let start = self.index(self.document.items.len());
+        self.document.items.extend_from_slice(&items);
+        let len = self.index(items.len());
+        return self.push(Node::Array { start, len });
+    }
+
+    fn push_object(&mut self, mut entries: Vec<(u32, u32)>) -> u32 {
+        let symbols = &self.document.symbols;
+        entries.sort_by(|a, b| symbols[a.0 as usize].cmp(&symbols[b.0 as usize]));
+        let start = self.index(self.document.entries.len());
+        self.document.entries.extend_from_slice(&entries);
+        let len = self.index(entries.len());
+        return self.push(Node::Object { start, len });
+    }
+
+    // Every value except strings, enums, arrays and objects.
+    fn scalar_node(&mut self, value: Value) -> Node {
+        return match value {
+            Value::Null => Node::Null,
+            Value::Bool(b) => Node::Bool(b),
+            Value::Integer(i) if i64::try_from(i).is_ok() => Node::Integer(i as i64),
+            Value::Float(f) => Node::Float(f),
+            Value::Char(c) => Node::Char(c),
+            _ => {
+                self.document.others.push(value);
+                Node::Other(self.index(self.document.others.len() - 1))
+            }
+        };
+    }
+
+    fn push_scalar(&mut self, value: Value) -> u32 {
+        let node = self.scalar_node(value);
+        return self.push(node);
+    }
+
+    fn push_value(&mut self, value: &Value) -> u32 {
+        return match value {
+            Value::String(s) => self.push_string(s),
+            Value::Enum(e) if e.qualifier().is_none() => {
+                let symbol = self.intern(e.name());
+                self.push(Node::Enum(symbol))
+            }
+            Value::Array(a) => {
+                let items = a.iter().map(|item| self.push_value(item)).collect();
+                self.push_array(items)
+            }
+            Value::Object(o) => {
+                let entries = o
+                    .iter()
+                    .map(|(key, value)| (self.intern(key), self.push_value(value)))
+                    .collect();
+                self.push_object(entries)
+            }
+            _ => self.push_scalar(value.clone()),
+        };
+    }
+}
+
+// Builds a document while parsing, so no tree of values is held in memory alongside it. Strings and keys are read
+// from the input like `BorrowedParser` does.
+struct ParseBuilder<'a> {
+    input: BorrowedBuilder<'a>,
+    builder: Builder,
+}
+
+impl<'a> ValueBuilder for ParseBuilder<'a> {
+    type Value = u32;
+    type Key = Cow<'a, str>;
+
+    fn scalar(&mut self, value: Value) -> u32 {
+        return self.builder.push_scalar(value);
+    }
+
+    fn string(&mut self, token: &Token, offset: usize) -> u32 {
+        let string = self.input.string_literal(token, offset);
+        return self.builder.push_string(&string);
+    }
+
+    fn enum_variant(&mut self, token: &Token, offset: usize) -> u32 {
+        let source = self.input.token_source(token, offset);
+        if variant::split_source(source).0.is_some() {
+            return self.builder.push_scalar(Value::Enum(EnumVariant::from_source(source)));
+        }
+        let symbol = self.builder.intern(source);
+        return self.builder.push(Node::Enum(symbol));
+    }
+
+    fn key(&mut self, token: &Token, offset: usize) -> Cow<'a, str> {
+        return self.input.key(token, offset);
+    }
+
+    // Only numbers and booleans are negated, and they are always the last node pushed.
+    fn negate(&mut self, node: u32) -> u32 {
+        let document = &self.builder.document;
+        let negated = CompactValue { document, node }.to_value().negate();
+        if let Node::Other(other) = document.nodes[node as usize] {
+            self.builder.document.others.truncate(other as usize);
+        }
+        self.builder.document.nodes[node as usize] = self.builder.scalar_node(negated);
+        return node;
+    }
+
+    fn is_null(&self, node: &u32) -> bool {
+        return matches!(self.builder.document.nodes[*node as usize], Node::Null);
+    }
+
+    fn array(&mut self, items: Vec<u32>) -> u32 {
+        return self.builder.push_array(items);
+    }
+
+    fn object(&mut self, fields: HashMap<Cow<'a, str>, u32>) -> u32 {
+        let entries = fields
+            .into_iter()
+            .map(|(key, node)| (self.builder.intern(&key), node))
+            .collect();
+        return self.builder.push_object(entries);
+    }
+}
+
+impl CompactDocument {
+    pub fn from_value(value: &Value) -> Result<CompactDocument, Error> {
+        let mut builder = Builder::new();
+        let root = builder.push_value(value);
+        return builder.finish(root);
+    }
+
+    // Parses straight into the document's buffers, without building a `Value` or `BorrowedValue` tree first.
+    pub fn parse(str: &str) -> Result<CompactDocument, Error> {
+        let builder = ParseBuilder {
+            input: BorrowedBuilder::new(str),
+            builder: Builder::new(),
+        };
+        let mut grammar = Grammar::new(str.as_bytes(), ParseOptions::default(), builder);
+        let root = grammar.parse()?;
+        return grammar.into_builder().builder.finish(root);
+    }
+
+    pub fn root(&self) -> CompactValue<'_> {
+        return CompactValue {
+            document: self,
+            node: self.root,
+        };
+    }
+
+    pub fn to_value(&self) -> Value {
+        return self.root().to_value();
+    }
+
+    // An estimate of the bytes allocated by the document, comparable to `Value::heap_size`.
+    pub fn heap_size(&self) -> usize {
+        return self.nodes.capacity() * size_of::<Node>()
+            + self.items.capacity() * size_of::<u32>()
+            + self.entries.capacity() * size_of::<(u32, u32)>()
+            + self.symbols.capacity() * size_of::<Box<str>>()
+            + self.symbols.iter().map(|symbol| symbol.len()).sum::<usize>()
+            + self.text.capacity()
+            + self.others.capacity() * size_of::<Value>()
+            + self.others.iter().map(Value::heap_size).sum::<usize>();
+    }
+}
+
+impl TryFrom<&Value> for CompactDocument {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        return CompactDocument::from_value(value);
+    }
+}
+
+// A value inside a `CompactDocument`.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactValue<'a> {
+    document: &'a CompactDocument,
+    node: u32,
+}
+
+impl<'a> CompactValue<'a> {
+    fn node(&self) -> Node {
+        return self.document.nodes[self.node as usize];
+    }
+
+    fn at(&self, node: u32) -> CompactValue<'a> {
+        return CompactValue {
+            document: self.document,
+            node,
+        };
+    }
+
+    fn entries(&self) -> &'a [(u32, u32)] {
+        return match self.node() {
+            Node::Object { start, len } => &self.document.entries[start as usize..(start + len) as usize],
+            _ => &[],
+        };
+    }
+
+    fn items(&self) -> &'a [u32] {
+        return match self.node() {
+            Node::Array { start, len } => &self.document.items[start as usize..(start + len) as usize],
+            _ => &[],
+        };
+    }
+
+    pub fn get_type(&self) -> String {
+        return match self.node() {
+            Node::Null => "Null".to_string(),
+            Node::Bool(_) => "Bool".to_string(),
+            Node::Integer(_) => "Integer".to_string(),
+            Node::Float(_) => "Float".to_string(),
+            Node::Char(_) => "Char".to_string(),
+            Node::InlineString { .. } | Node::String { .. } => "String".to_string(),
+            Node::Enum(_) => "Enum".to_string(),
+            Node::Array { .. } => "Array".to_string(),
+            Node::Object { .. } => "Object".to_string(),
+            Node::Other(other) => self.document.others[other as usize].get_type(),
+        };
+    }
+
+    // Looks up a field of an object.
+    pub fn get(&self, key: &str) -> Option<CompactValue<'a>> {
+        let symbols = &self.document.symbols;
+        let entries = self.entries();
+        let position = entries
+            .binary_search_by(|(symbol, _)| (*symbols[*symbol as usize]).cmp(key))
+            .ok()?;
+        return Some(self.at(entries[position].1));
+    }
+
+    // Looks up an item of an array.
+    pub fn get_index(&self, index: usize) -> Option<CompactValue<'a>> {
+        return self.items().get(index).map(|node| self.at(*node));
+    }
+
+    pub fn get_path(&self, path: &Path) -> Option<CompactValue<'a>> {
+        let mut value = *self;
+        for segment in path.segments() {
+            value = match segment {
+                PathSegment::Key(key) => value.get(key)?,
+                PathSegment::Index(index) => value.get_index(*index)?,
+            };
+        }
+        return Some(value);
+    }
+
+    // The number of items of an array or entries of an object, zero for other values.
+    pub fn len(&self) -> usize {
+        return self.items().len() + self.entries().len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    // The items of an array, empty for other values.
+    pub fn iter(&self) -> impl Iterator<Item = CompactValue<'a>> + 'a {
+        let value = *self;
+        return self.items().iter().map(move |node| value.at(*node));
+    }
+
+    // The entries of an object sorted by key, empty for other values.
+    pub fn entries_iter(&self) -> impl Iterator<Item = (&'a str, CompactValue<'a>)> + 'a {
+        let value = *self;
+        return self
+            .entries()
+            .iter()
+            .map(move |(symbol, node)| (&*value.document.symbols[*symbol as usize], value.at(*node)));
+    }
+
+    // The text of a string or the name of an enum variant.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let document = self.document;
+        return match &document.nodes[self.node as usize] {
+            Node::InlineString { len, bytes } => Some(std::str::from_utf8(&bytes[..*len as usize]).unwrap()),
+            Node::String { start, len } => Some(&document.text[*start as usize..(*start + *len) as usize]),
+            Node::Enum(symbol) => Some(&document.symbols[*symbol as usize]),
+            _ => None,
+        };
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        return match self.node() {
+            Node::Bool(b) => Some(b),
+            _ => None,
+        };
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        return match self.node() {
+            Node::Integer(i) => Some(i as i128),
+            Node::Other(other) => match &self.document.others[other as usize] {
+                Value::Integer(i) => Some(*i),
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        return match self.node() {
+            Node::Float(f) => Some(f),
+            _ => None,
+        };
+    }
+
+    pub fn to_value(&self) -> Value {
+        return match self.node() {
+            Node::Null => Value::Null,
+            Node::Bool(b) => Value::Bool(b),
+            Node::Integer(i) => Value::Integer(i as i128),
+            Node::Float(f) => Value::Float(f),
+            Node::Char(c) => Value::Char(c),
+            Node::InlineString { .. } | Node::String { .. } => Value::String(self.as_str().unwrap().to_string()),
+            Node::Enum(_) => Value::Enum(EnumVariant::new(self.as_str().unwrap())),
+            Node::Array { .. } => Value::Array(self.iter().map(|item| item.to_value()).collect()),
+            Node::Object { .. } => Value::Object(
+                self.entries_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_value()))
+                    .collect(),
+            ),
+            Node::Other(other) => self.document.others[other as usize].clone(),
+        };
+    }
+}
+
+// Approximates the buckets and control bytes of the standard `HashMap`.
+fn hash_map_heap_size<K, V>(map: &HashMap<K, V>) -> usize {
+    if map.capacity() == 0 {
+        return 0;
+    }
+    let buckets = (map.capacity() * 8 / 7).next_power_of_two();
+    return buckets * (size_of::<(K, V)>() + 1);
+}
+
+impl Value {
+    // An estimate of the bytes this value has allocated on the heap, not counting the `Value` itself.
+    pub fn heap_size(&self) -> usize {
+        return match self {
+            Value::Null
+            | Value::Bool(_)
+            | Value::Float(_)
+            | Value::Integer(_)
+            | Value::Char(_)
+            | Value::DateTime(_)
+            | Value::Duration(_)
+            | Value::ByteSize(_) => 0,
+            Value::BigInteger(b) => b.heap_size(),
+            Value::Number(n) => n.heap_size(),
+            Value::String(s) => s.capacity(),
+            Value::Enum(e) => e.heap_size(),
+            Value::Bytes(b) => b.capacity(),
+            Value::Raw(r) => r.heap_size(),
+            Value::Array(a) => a.capacity() * size_of::<Value>() + a.iter().map(Value::heap_size).sum::<usize>(),
+            Value::Object(o) => {
+                hash_map_heap_size(o)
+                    + o.iter()
+                        .map(|(key, value)| key.capacity() + value.heap_size())
+                        .sum::<usize>()
+            }
+        };
+    }
+}
@@ -0,0 +1,262 @@
+use crate::Value;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+// Equality, hashing and a total order for `Value`, so values can be used as `HashSet` or `BTreeMap` keys, sorted, and
+// deduplicated.
+//
+// Values of different kinds are ordered as:
+//   Null < Bool < numbers < Char < String < Enum < Bytes < DateTime < Duration < ByteSize < Raw < Array < Object
+//
+// `Integer`, `BigInteger`, `Float` and `Number` are compared by their exact numeric value, with `-inf` first and
+// `+inf` then `NaN` last. Numerically equal values of different variants are still distinct and are ordered
+// `Integer < BigInteger < Float < Number`, so `1 < 1.0`. Numbers with equal values but different literals, such as
+// `19.1` and `19.10`, are ordered by their literal text.
+//
+// Floats are treated canonically: every `NaN` is equal to every other `NaN`, and `-0.0` is equal to `0.0`.
+//
+// Arrays compare element by element, and objects compare their entries sorted by key. Date times compare by their
+// fields rather than by instant, so the same instant written with different offsets is not equal. Raw values compare
+// by their source text, and enum variants by their type name, unqualified first, then by their name.
+
+fn kind_rank(value: &Value) -> u8 {
+    return match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Integer(_) | Value::BigInteger(_) | Value::Float(_) | Value::Number(_) => 2,
+        Value::Char(_) => 3,
+        Value::String(_) => 4,
+        Value::Enum(_) => 5,
+        Value::Bytes(_) => 6,
+        Value::DateTime(_) => 7,
+        Value::Duration(_) => 8,
+        Value::ByteSize(_) => 9,
+        Value::Raw(_) => 10,
+        Value::Array(_) => 11,
+        Value::Object(_) => 12,
+    };
+}
+
+// Tie-breaker between numerically equal values of different numeric variants.
+fn numeric_rank(value: &Value) -> u8 {
+    return match value {
+        Value::Integer(_) => 0,
+        Value::BigInteger(_) => 1,
+        Value::Float(_) => 2,
+        _ => 3,
+    };
+}
+
+// An exact decimal: the integer digits without leading zeros and the fraction digits without trailing zeros.
+#[derive(PartialEq, Eq)]
+struct Decimal {
+    negative: bool,
+    integer: String,
+    fraction: String,
+}
+
+impl Decimal {
+    fn parse(text: &str) -> Decimal {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let integer = integer.trim_start_matches('0').to_string();
+        let fraction = fraction.trim_end_matches('0').to_string();
+        let negative = negative && !(integer.is_empty() && fraction.is_empty());
+        return Decimal {
+            negative,
+            integer,
+            fraction,
+        };
+    }
+
+    // The exact value of a finite float, which needs at most as many fraction digits as its binary exponent.
+    fn from_f64(f: f64) -> Decimal {
+        let bits = f.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let mut mantissa = bits & ((1 << 52) - 1);
+        let mut exponent = if exponent == 0 {
+            -1074
+        } else {
+            mantissa |= 1 << 52;
+            exponent - 1075
+        };
+        while mantissa != 0 && mantissa.is_multiple_of(2) && exponent < 0 {
+            mantissa /= 2;
+            exponent += 1;
+        }
+        let fraction_digits = (-exponent).max(0) as usize;
+        return Decimal::parse(&format!("{:.*}", fraction_digits, f));
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude = self
+            .integer
+            .len()
+            .cmp(&other.integer.len())
+            .then_with(|| self.integer.cmp(&other.integer))
+            // Without trailing zeros, fraction digits order the same way as their values.
+            .then_with(|| self.fraction.cmp(&other.fraction));
+        return match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        };
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+// Variants are declared in ascending order.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum NumericKey {
+    NegativeInfinity,
+    Finite(Decimal),
+    PositiveInfinity,
+    NaN,
+}
+
+fn numeric_key(value: &Value) -> NumericKey {
+    return match value {
+        Value::Integer(i) => NumericKey::Finite(Decimal::parse(&i.to_string())),
+        Value::BigInteger(b) => NumericKey::Finite(Decimal::parse(&b.to_string())),
+        Value::Number(n) => NumericKey::Finite(Decimal::parse(n.as_str())),
+        Value::Float(f) if f.is_nan() => NumericKey::NaN,
+        Value::Float(f) if *f == f64::INFINITY => NumericKey::PositiveInfinity,
+        Value::Float(f) if *f == f64::NEG_INFINITY => NumericKey::NegativeInfinity,
+        Value::Float(f) => NumericKey::Finite(Decimal::from_f64(*f)),
+        _ => unreachable!("not a number"),
+    };
+}
+
+fn float_cmp(a: f64, b: f64) -> Ordering {
+    return match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    };
+}
+
+fn numeric_cmp(a: &Value, b: &Value) -> Ordering {
+    let value_order = match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Float(a), Value::Float(b)) => float_cmp(*a, *b),
+        _ => numeric_key(a).cmp(&numeric_key(b)),
+    };
+    return value_order
+        .then_with(|| numeric_rank(a).cmp(&numeric_rank(b)))
+        .then_with(|| {
+            return match (a, b) {
+                (Value::Number(a), Value::Number(b)) => a.as_str().cmp(b.as_str()),
+                _ => Ordering::Equal,
+            };
+        });
+}
+
+fn sorted_entries(object: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<(&String, &Value)> = object.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    return entries;
+}
+
+// Every NaN hashes the same, and `-0.0` hashes like `0.0`.
+fn canonical_float_bits(f: f64) -> u64 {
+    if f.is_nan() {
+        return f64::NAN.to_bits();
+    }
+    if f == 0.0 {
+        return 0;
+    }
+    return f.to_bits();
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        return match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::BigInteger(a), Value::BigInteger(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::Enum(a), Value::Enum(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::DateTime(a), Value::DateTime(b)) => a == b,
+            (Value::Duration(a), Value::Duration(b)) => a == b,
+            (Value::ByteSize(a), Value::ByteSize(b)) => a == b,
+            (Value::Raw(a), Value::Raw(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            _ => false,
+        };
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Null => (),
+            Value::Bool(b) => b.hash(state),
+            Value::Float(f) => canonical_float_bits(*f).hash(state),
+            Value::Integer(i) => i.hash(state),
+            Value::BigInteger(b) => b.hash(state),
+            Value::Number(n) => n.hash(state),
+            Value::String(s) => s.hash(state),
+            Value::Char(c) => c.hash(state),
+            Value::Enum(e) => e.hash(state),
+            Value::Bytes(b) => b.hash(state),
+            Value::DateTime(d) => d.hash(state),
+            Value::Duration(d) => d.hash(state),
+            Value::ByteSize(s) => s.hash(state),
+            Value::Raw(r) => r.hash(state),
+            Value::Array(a) => a.hash(state),
+            // Hashed in key order, so equal objects hash the same regardless of insertion order.
+            Value::Object(o) => sorted_entries(o).hash(state),
+        }
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Char(a), Value::Char(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Enum(a), Value::Enum(b)) => a.cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::DateTime(a), Value::DateTime(b)) => a.cmp(b),
+            (Value::Duration(a), Value::Duration(b)) => a.cmp(b),
+            (Value::ByteSize(a), Value::ByteSize(b)) => a.cmp(b),
+            (Value::Raw(a), Value::Raw(b)) => a.as_str().cmp(b.as_str()),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => sorted_entries(a).cmp(&sorted_entries(b)),
+            _ if kind_rank(self) == 2 && kind_rank(other) == 2 => numeric_cmp(self, other),
+            _ => kind_rank(self).cmp(&kind_rank(other)),
+        };
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
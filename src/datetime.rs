@@ -0,0 +1,440 @@
+use crate::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// RFC 3339 dates and times, written unquoted in SON: `2024-01-15`, `08:30:00`, `2024-01-15T08:30:00.250` and
+// `2024-01-15T08:30:00+02:00`. Only the calendar arithmetic needed to validate them and to convert offset date times
+// to and from `SystemTime` is implemented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DateTime {
+    Date(Date),
+    Time(Time),
+    // A date and time without an offset, which doesn't identify a single instant.
+    Local(Date, Time),
+    Offset(Date, Time, Offset),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+    // Up to 60 to allow leap seconds.
+    second: u8,
+    nanosecond: u32,
+}
+
+// The offset from UTC in minutes, `Z` is an offset of zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Offset {
+    minutes: i16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateTimeError {
+    pub message: String,
+}
+
+impl Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl std::error::Error for ParseDateTimeError {}
+
+fn invalid(message: String) -> ParseDateTimeError {
+    return ParseDateTimeError { message };
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    return year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    return match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+}
+
+impl Date {
+    // Years are limited to 0000 through 9999, the ones that can be written with four digits.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+        if !(0..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        return Some(Date { year, month, day });
+    }
+
+    pub fn year(&self) -> i32 {
+        return self.year;
+    }
+
+    pub fn month(&self) -> u8 {
+        return self.month;
+    }
+
+    pub fn day(&self) -> u8 {
+        return self.day;
+    }
+
+    // Days since 1970-01-01, using the proleptic Gregorian calendar.
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = if self.month <= 2 { self.year - 1 } else { self.year } as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_from_march = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * month_from_march + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        return era * 146097 + day_of_era - 719468;
+    }
+
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        } as u8;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        return Date {
+            year: year as i32,
+            month,
+            day,
+        };
+    }
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8, second: u8, nanosecond: u32) -> Option<Time> {
+        if hour > 23 || minute > 59 || second > 60 || nanosecond >= 1_000_000_000 {
+            return None;
+        }
+        return Some(Time {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        });
+    }
+
+    pub fn hour(&self) -> u8 {
+        return self.hour;
+    }
+
+    pub fn minute(&self) -> u8 {
+        return self.minute;
+    }
+
+    pub fn second(&self) -> u8 {
+        return self.second;
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        return self.nanosecond;
+    }
+
+    fn seconds_since_midnight(&self) -> i64 {
+        return self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+    }
+}
+
+impl Offset {
+    pub const UTC: Offset = Offset { minutes: 0 };
+
+    pub fn new(minutes: i16) -> Option<Offset> {
+        if minutes.abs() >= 24 * 60 {
+            return None;
+        }
+        return Some(Offset { minutes });
+    }
+
+    pub fn minutes(&self) -> i16 {
+        return self.minutes;
+    }
+}
+
+impl DateTime {
+    pub fn date(&self) -> Option<Date> {
+        return match self {
+            DateTime::Date(date) | DateTime::Local(date, _) | DateTime::Offset(date, _, _) => Some(*date),
+            DateTime::Time(_) => None,
+        };
+    }
+
+    pub fn time(&self) -> Option<Time> {
+        return match self {
+            DateTime::Time(time) | DateTime::Local(_, time) | DateTime::Offset(_, time, _) => Some(*time),
+            DateTime::Date(_) => None,
+        };
+    }
+
+    pub fn offset(&self) -> Option<Offset> {
+        return match self {
+            DateTime::Offset(_, _, offset) => Some(*offset),
+            _ => None,
+        };
+    }
+
+    // The instant in UTC, only available for offset date times.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let DateTime::Offset(date, time, offset) = self else {
+            return None;
+        };
+        let seconds = date.days_since_epoch() * 86400 + time.seconds_since_midnight() - offset.minutes as i64 * 60;
+        let instant = if seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64))?
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))?
+        };
+        return instant.checked_add(Duration::from_nanos(time.nanosecond as u64));
+    }
+
+    // The instant in UTC, or `None` if it falls outside of the years 0000 to 9999.
+    pub fn from_system_time(instant: SystemTime) -> Option<DateTime> {
+        let (seconds, nanosecond) = match instant.duration_since(UNIX_EPOCH) {
+            Ok(since) => (i64::try_from(since.as_secs()).ok()?, since.subsec_nanos()),
+            Err(error) => {
+                let before = error.duration();
+                let seconds = i64::try_from(before.as_secs()).ok()?;
+                match before.subsec_nanos() {
+                    0 => (-seconds, 0),
+                    nanos => (-seconds - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+        let days = seconds.div_euclid(86400);
+        let first = Date::new(0, 1, 1)?.days_since_epoch();
+        let last = Date::new(9999, 12, 31)?.days_since_epoch();
+        if !(first..=last).contains(&days) {
+            return None;
+        }
+        let date = Date::from_days_since_epoch(days);
+        let seconds_of_day = seconds.rem_euclid(86400);
+        let time = Time {
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day % 3600 / 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+            nanosecond,
+        };
+        return Some(DateTime::Offset(date, time, Offset::UTC));
+    }
+}
+
+// Parsing
+
+fn parse_digits(s: &str, what: &str) -> Result<u32, ParseDateTimeError> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid(format!("expected digits for the {}, found '{}'", what, s)));
+    }
+    return Ok(s.parse::<u32>().unwrap());
+}
+
+fn parse_date(s: &str) -> Result<Date, ParseDateTimeError> {
+    let bytes = s.as_bytes();
+    if s.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return Err(invalid(format!("'{}' is not a date in the YYYY-MM-DD format", s)));
+    }
+    let year = parse_digits(&s[0..4], "year")? as i32;
+    let month = parse_digits(&s[5..7], "month")? as u8;
+    let day = parse_digits(&s[8..10], "day")? as u8;
+    if !(1..=12).contains(&month) {
+        return Err(invalid(format!("month {} is out of range", month)));
+    }
+    return Date::new(year, month, day).ok_or_else(|| {
+        return invalid(format!("day {} is out of range for {}-{:02}", day, year, month));
+    });
+}
+
+// Returns the parsed time and whatever follows it.
+fn parse_time(s: &str) -> Result<(Time, &str), ParseDateTimeError> {
+    let bytes = s.as_bytes();
+    if s.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return Err(invalid(format!("'{}' is not a time in the HH:MM:SS format", s)));
+    }
+    let hour = parse_digits(&s[0..2], "hour")? as u8;
+    let minute = parse_digits(&s[3..5], "minute")? as u8;
+    let second = parse_digits(&s[6..8], "second")? as u8;
+
+    let mut rest = &s[8..];
+    let mut nanosecond = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.find(|c: char| !c.is_ascii_digit()).unwrap_or(fraction.len());
+        if digits == 0 || digits > 9 {
+            return Err(invalid(
+                "fractional seconds must have between 1 and 9 digits".to_string(),
+            ));
+        }
+        nanosecond = parse_digits(&fraction[..digits], "fractional seconds")? * 10u32.pow(9 - digits as u32);
+        rest = &fraction[digits..];
+    }
+
+    let time = Time::new(hour, minute, second, nanosecond).ok_or_else(|| {
+        return invalid(format!("{:02}:{:02}:{:02} is not a valid time", hour, minute, second));
+    })?;
+    return Ok((time, rest));
+}
+
+fn parse_offset(s: &str) -> Result<Offset, ParseDateTimeError> {
+    if s == "Z" || s == "z" {
+        return Ok(Offset::UTC);
+    }
+    let sign = match s.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(invalid(format!("'{}' is not a valid offset", s))),
+    };
+    if s.len() != 6 || s.as_bytes()[3] != b':' {
+        return Err(invalid(format!("'{}' is not an offset in the +HH:MM format", s)));
+    }
+    let hours = parse_digits(&s[1..3], "offset hours")?;
+    let minutes = parse_digits(&s[4..6], "offset minutes")?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid(format!("offset {} is out of range", s)));
+    }
+    return Ok(Offset {
+        minutes: sign * (hours * 60 + minutes) as i16,
+    });
+}
+
+impl FromStr for DateTime {
+    type Err = ParseDateTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() {
+            return Err(invalid(format!("'{}' is not a date or time", s)));
+        }
+        if s.len() < 10 || s.as_bytes()[4] != b'-' {
+            return match parse_time(s)? {
+                (time, "") => Ok(DateTime::Time(time)),
+                (_, rest) => Err(invalid(format!("unexpected '{}' after a time", rest))),
+            };
+        }
+
+        let date = parse_date(&s[..10])?;
+        let rest = &s[10..];
+        if rest.is_empty() {
+            return Ok(DateTime::Date(date));
+        }
+        let Some(rest) = rest.strip_prefix(['T', 't']) else {
+            return Err(invalid(format!(
+                "expected 'T' between the date and time, found '{}'",
+                rest
+            )));
+        };
+        return match parse_time(rest)? {
+            (time, "") => Ok(DateTime::Local(date, time)),
+            (time, offset) => Ok(DateTime::Offset(date, time, parse_offset(offset)?)),
+        };
+    }
+}
+
+// Printing
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day);
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        return Ok(());
+    }
+}
+
+impl Display for Offset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "Z");
+        }
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        let minutes = self.minutes.unsigned_abs();
+        return write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60);
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            DateTime::Date(date) => write!(f, "{}", date),
+            DateTime::Time(time) => write!(f, "{}", time),
+            DateTime::Local(date, time) => write!(f, "{}T{}", date, time),
+            DateTime::Offset(date, time, offset) => write!(f, "{}T{}{}", date, time, offset),
+        };
+    }
+}
+
+// Conversions
+
+impl Serialize for DateTime {}
+impl ToSon for DateTime {
+    fn to_son(&self) -> Value {
+        return Value::DateTime(*self);
+    }
+}
+
+impl Deserialize for DateTime {}
+impl FromSon for DateTime {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::DateTime(d) => Ok(d),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "DateTime".to_string(),
+                found: son.get_type(),
+            }),
+        };
+    }
+}
+
+// Written as a UTC offset date time, with as many fractional digits as needed. Instants outside of the years 0000 to
+// 9999 can't be written as a date time and are serialized as `null`.
+impl Serialize for SystemTime {}
+impl ToSon for SystemTime {
+    fn to_son(&self) -> Value {
+        return match DateTime::from_system_time(*self) {
+            Some(datetime) => Value::DateTime(datetime),
+            None => Value::Null,
+        };
+    }
+}
+
+impl Deserialize for SystemTime {}
+impl FromSon for SystemTime {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        let datetime = DateTime::from_son(son)?;
+        if datetime.offset().is_none() {
+            return Err(DeserializationError::InvalidValue {
+                message: format!("{} has no offset, so it is not a point in time", datetime),
+            });
+        }
+        return datetime
+            .to_system_time()
+            .ok_or_else(|| DeserializationError::InvalidValue {
+                message: format!("{} is out of the range of SystemTime", datetime),
+            });
+    }
+}
@@ -1,9 +1,22 @@
-use crate::{error::DeserializationError, value::Value};
+use crate::{error::DeserializationError, path::Path, value::Value};
 use std::collections::HashMap;
 
 pub trait Deserialize: FromSon {}
 pub trait FromSon: Sized {
     fn from_son(son: Value) -> Result<Self, DeserializationError>;
+
+    // Used by `Vec<Self>`, so element types can accept other representations of a list of themselves.
+    fn vec_from_son(son: Value) -> Result<Vec<Self>, DeserializationError> {
+        return match son {
+            Value::Array(a) => a.into_iter().map(Self::from_son).collect(),
+            _ => Err(default_error(Value::Array(Vec::new()), son)),
+        };
+    }
+
+    // Paths, relative to this type's value, whose source text the parser keeps as `Value::Raw` for `RawSon` fields.
+    fn raw_paths() -> Vec<Path> {
+        return Vec::new();
+    }
 }
 
 fn default_error(expected: Value, found: Value) -> DeserializationError {
@@ -54,6 +67,7 @@ impl FromSon for i8 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as i8),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -64,6 +78,7 @@ impl FromSon for i16 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as i16),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -74,6 +89,7 @@ impl FromSon for i32 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as i32),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -84,6 +100,7 @@ impl FromSon for i64 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as i64),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -94,6 +111,7 @@ impl FromSon for i128 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -104,6 +122,7 @@ impl FromSon for isize {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as isize),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -116,9 +135,19 @@ impl FromSon for u8 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as u8),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
+
+    // Binary data can be read from a byte string as well as from an array of integers.
+    fn vec_from_son(son: Value) -> Result<Vec<Self>, DeserializationError> {
+        return match son {
+            Value::Bytes(b) => Ok(b),
+            Value::Array(a) => a.into_iter().map(Self::from_son).collect(),
+            _ => Err(default_error(Value::Array(Vec::new()), son)),
+        };
+    }
 }
 
 impl Deserialize for u16 {}
@@ -126,6 +155,7 @@ impl FromSon for u16 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as u16),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -136,6 +166,7 @@ impl FromSon for u32 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as u32),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -146,6 +177,7 @@ impl FromSon for u64 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as u64),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -154,8 +186,13 @@ impl FromSon for u64 {
 impl Deserialize for u128 {}
 impl FromSon for u128 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        let out_of_range = |value: String| DeserializationError::InvalidValue {
+            message: format!("{} is out of range for u128", value),
+        };
         match son {
-            Value::Integer(i) => Ok(i as u128),
+            Value::Integer(i) => u128::try_from(i).map_err(|_| out_of_range(i.to_string())),
+            Value::BigInteger(b) => b.to_u128().ok_or_else(|| out_of_range(b.to_string())),
+            Value::Number(n) => n.to_u128().ok_or_else(|| out_of_range(n.to_string())),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -166,6 +203,7 @@ impl FromSon for usize {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
             Value::Integer(i) => Ok(i as usize),
+            Value::Number(n) => n.to_integer(),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -177,7 +215,9 @@ impl Deserialize for f32 {}
 impl FromSon for f32 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
+            Value::Float(f) => Ok(f as f32),
             Value::Integer(i) => Ok(i as f32),
+            Value::Number(n) => Ok(n.to_f64() as f32),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -187,7 +227,9 @@ impl Deserialize for f64 {}
 impl FromSon for f64 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
         match son {
+            Value::Float(f) => Ok(f),
             Value::Integer(i) => Ok(i as f64),
+            Value::Number(n) => Ok(n.to_f64()),
             _ => Err(default_error(Value::Integer(0), son)),
         }
     }
@@ -201,10 +243,7 @@ where
     T: Deserialize,
 {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
-        match son {
-            Value::Array(a) => Ok(a.iter().map(|v| T::from_son(v.clone()).unwrap()).collect()),
-            _ => Err(default_error(Value::Array(Vec::new()), son)),
-        }
+        return T::vec_from_son(son);
     }
 }
 
@@ -0,0 +1,394 @@
+use crate::{
+    Deserialize, Serialize,
+    deserialize::FromSon,
+    error::{DeserializationError, PatchError},
+    path::{Path, PathSegment},
+    serialize::ToSon,
+    value::Value,
+    variant::EnumVariant,
+};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+// A single change to a Value. `Remove`, `Replace` and `Move` carry the previous value so a patch can check that it
+// is applied to the document it was computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Add { path: Path, value: Value },
+    Remove { path: Path, old: Value },
+    Replace { path: Path, old: Value, value: Value },
+    Move { from: Path, path: Path, old: Value },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Patch {
+    pub operations: Vec<Operation>,
+}
+
+impl Patch {
+    pub fn new(operations: Vec<Operation>) -> Self {
+        return Self { operations };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.operations.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.operations.len();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Operation> {
+        return self.operations.iter();
+    }
+}
+
+// Computes the operations turning `a` into `b`.
+//
+// Objects are compared key by key and arrays position by position: elements are diffed pairwise up to the shorter
+// length, the remaining elements are added or removed at the end. A key removed from one object and added with an
+// identical value to another object is reported as a single `Move`.
+pub fn diff(a: &Value, b: &Value) -> Patch {
+    let mut operations = Vec::new();
+    diff_inner(a, b, &Path::root(), &mut operations);
+    detect_moves(&mut operations);
+    return Patch { operations };
+}
+
+fn diff_inner(a: &Value, b: &Value, path: &Path, operations: &mut Vec<Operation>) {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k))).collect();
+            keys.sort();
+
+            for key in keys {
+                let key_path = path.key(key);
+                match (a.get(key), b.get(key)) {
+                    (Some(old), Some(new)) => diff_inner(old, new, &key_path, operations),
+                    (Some(old), None) => operations.push(Operation::Remove {
+                        path: key_path,
+                        old: old.clone(),
+                    }),
+                    (None, Some(new)) => operations.push(Operation::Add {
+                        path: key_path,
+                        value: new.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let common = a.len().min(b.len());
+            for index in 0..common {
+                diff_inner(&a[index], &b[index], &path.index(index), operations);
+            }
+            for (index, new) in b.iter().enumerate().skip(common) {
+                operations.push(Operation::Add {
+                    path: path.index(index),
+                    value: new.clone(),
+                });
+            }
+            // Removed from the end so the indices of the remaining elements stay valid.
+            for index in (common..a.len()).rev() {
+                operations.push(Operation::Remove {
+                    path: path.index(index),
+                    old: a[index].clone(),
+                });
+            }
+        }
+        _ => {
+            if a != b {
+                operations.push(Operation::Replace {
+                    path: path.clone(),
+                    old: a.clone(),
+                    value: b.clone(),
+                });
+            }
+        }
+    }
+}
+
+// Object keys never shift, so a removed key and an added key with the same value can be merged into a move
+// without invalidating the paths of the other operations.
+fn detect_moves(operations: &mut Vec<Operation>) {
+    let is_key = |path: &Path| matches!(path.last(), Some(PathSegment::Key(_)));
+    let mut index = 0;
+    while index < operations.len() {
+        let Operation::Add { path, value } = &operations[index] else {
+            index += 1;
+            continue;
+        };
+        if !is_key(path) {
+            index += 1;
+            continue;
+        }
+
+        let removed = operations
+            .iter()
+            .position(|operation| matches!(operation, Operation::Remove { path, old } if is_key(path) && old == value));
+        match removed {
+            Some(removed) => {
+                let Operation::Remove { path: from, old } = operations.remove(removed) else {
+                    unreachable!();
+                };
+                if removed < index {
+                    index -= 1;
+                }
+                let path = match &operations[index] {
+                    Operation::Add { path, .. } => path.clone(),
+                    _ => unreachable!(),
+                };
+                operations[index] = Operation::Move { from, path, old };
+            }
+            None => index += 1,
+        }
+    }
+}
+
+// Applies every operation of the patch in order. The value is only modified if all operations succeed.
+pub fn apply_patch(value: &mut Value, patch: &Patch) -> Result<(), PatchError> {
+    let mut result = value.clone();
+    for operation in patch.iter() {
+        apply_operation(&mut result, operation)?;
+    }
+    *value = result;
+    return Ok(());
+}
+
+impl Value {
+    pub fn diff(&self, other: &Value) -> Patch {
+        return diff(self, other);
+    }
+
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<(), PatchError> {
+        return apply_patch(self, patch);
+    }
+}
+
+fn apply_operation(value: &mut Value, operation: &Operation) -> Result<(), PatchError> {
+    return match operation {
+        Operation::Add { path, value: new } => insert(value, path, new.clone()),
+        Operation::Remove { path, old } => {
+            check_precondition(value, path, old)?;
+            remove(value, path).map(|_| ())
+        }
+        Operation::Replace { path, old, value: new } => {
+            check_precondition(value, path, old)?;
+            *value.get_path_mut(path).unwrap() = new.clone();
+            Ok(())
+        }
+        Operation::Move { from, path, old } => {
+            if path.starts_with(from) && path != from {
+                return Err(PatchError::InvalidTarget {
+                    path: path.to_string(),
+                    message: format!("cannot move {} into itself", from),
+                });
+            }
+            check_precondition(value, from, old)?;
+            let moved = remove(value, from)?;
+            insert(value, path, moved)
+        }
+    };
+}
+
+fn check_precondition(value: &Value, path: &Path, expected: &Value) -> Result<(), PatchError> {
+    return match value.get_path(path) {
+        None => Err(PatchError::PathNotFound { path: path.to_string() }),
+        Some(found) if found != expected => Err(PatchError::PreconditionFailed {
+            path: path.to_string(),
+            expected: expected.to_string().trim_end().to_string(),
+            found: found.to_string().trim_end().to_string(),
+        }),
+        Some(_) => Ok(()),
+    };
+}
+
+fn parent_of<'a>(value: &'a mut Value, path: &Path) -> Result<(&'a mut Value, PathSegment), PatchError> {
+    let (Some(parent), Some(last)) = (path.parent(), path.last()) else {
+        return Err(PatchError::InvalidTarget {
+            path: path.to_string(),
+            message: "the document root cannot be added or removed".to_string(),
+        });
+    };
+    return match value.get_path_mut(&parent) {
+        Some(container) => Ok((container, last.clone())),
+        None => Err(PatchError::PathNotFound {
+            path: parent.to_string(),
+        }),
+    };
+}
+
+fn insert(value: &mut Value, path: &Path, new: Value) -> Result<(), PatchError> {
+    let (container, last) = parent_of(value, path)?;
+    return match (container, last) {
+        (Value::Object(o), PathSegment::Key(key)) => {
+            if o.contains_key(&key) {
+                return Err(PatchError::PathExists { path: path.to_string() });
+            }
+            o.insert(key, new);
+            Ok(())
+        }
+        (Value::Array(a), PathSegment::Index(index)) if index <= a.len() => {
+            a.insert(index, new);
+            Ok(())
+        }
+        (Value::Array(_), PathSegment::Index(_)) => Err(PatchError::PathNotFound { path: path.to_string() }),
+        (container, _) => Err(PatchError::InvalidTarget {
+            path: path.to_string(),
+            message: format!("cannot insert into {}", container.get_type()),
+        }),
+    };
+}
+
+fn remove(value: &mut Value, path: &Path) -> Result<Value, PatchError> {
+    let (container, last) = parent_of(value, path)?;
+    let removed = match (container, last) {
+        (Value::Object(o), PathSegment::Key(key)) => o.remove(&key),
+        (Value::Array(a), PathSegment::Index(index)) if index < a.len() => Some(a.remove(index)),
+        _ => None,
+    };
+    return removed.ok_or_else(|| PatchError::PathNotFound { path: path.to_string() });
+}
+
+// Rendering
+
+fn display_value(value: &Value) -> String {
+    return value.to_string().trim_end().to_string();
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Operation::Add { path, value } => write!(f, "+ {}: {}", path, display_value(value)),
+            Operation::Remove { path, old } => write!(f, "- {}: {}", path, display_value(old)),
+            Operation::Replace { path, old, value } => {
+                write!(f, "~ {}: {} -> {}", path, display_value(old), display_value(value))
+            }
+            Operation::Move { from, path, .. } => write!(f, "> {} -> {}", from, path),
+        };
+    }
+}
+
+impl Display for Patch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for operation in self.iter() {
+            writeln!(f, "{}", operation)?;
+        }
+        return Ok(());
+    }
+}
+
+// Patches are serialized as an array of objects such as `{ op: Replace path: "$.port" old: 80 value: 8080 }`.
+
+impl Serialize for Operation {}
+impl ToSon for Operation {
+    fn to_son(&self) -> Value {
+        let mut map = HashMap::new();
+        let op = match self {
+            Operation::Add { path, value } => {
+                map.insert("path".to_string(), path.to_string().to_son());
+                map.insert("value".to_string(), value.clone());
+                "Add"
+            }
+            Operation::Remove { path, old } => {
+                map.insert("path".to_string(), path.to_string().to_son());
+                map.insert("old".to_string(), old.clone());
+                "Remove"
+            }
+            Operation::Replace { path, old, value } => {
+                map.insert("path".to_string(), path.to_string().to_son());
+                map.insert("old".to_string(), old.clone());
+                map.insert("value".to_string(), value.clone());
+                "Replace"
+            }
+            Operation::Move { from, path, old } => {
+                map.insert("from".to_string(), from.to_string().to_son());
+                map.insert("path".to_string(), path.to_string().to_son());
+                map.insert("old".to_string(), old.clone());
+                "Move"
+            }
+        };
+        map.insert("op".to_string(), Value::Enum(EnumVariant::new(op)));
+        return Value::Object(map);
+    }
+}
+
+impl Deserialize for Operation {}
+impl FromSon for Operation {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        let Value::Object(mut map) = son else {
+            return Err(DeserializationError::UnexpectedType {
+                expected: "Object".to_string(),
+                found: son.get_type(),
+            });
+        };
+
+        let mut take = |field: &str| -> Result<Value, DeserializationError> {
+            return map.remove(field).ok_or_else(|| DeserializationError::MissingField {
+                field: field.to_string(),
+            });
+        };
+        let take_path = |value: Value| -> Result<Path, DeserializationError> {
+            return String::from_son(value)?
+                .parse::<Path>()
+                .map_err(|e| DeserializationError::InvalidValue { message: e.to_string() });
+        };
+
+        let op = take("op")?;
+        return match op {
+            Value::Enum(op) if op == "Add" => Ok(Operation::Add {
+                path: take_path(take("path")?)?,
+                value: take("value")?,
+            }),
+            Value::Enum(op) if op == "Remove" => Ok(Operation::Remove {
+                path: take_path(take("path")?)?,
+                old: take("old")?,
+            }),
+            Value::Enum(op) if op == "Replace" => Ok(Operation::Replace {
+                path: take_path(take("path")?)?,
+                old: take("old")?,
+                value: take("value")?,
+            }),
+            Value::Enum(op) if op == "Move" => Ok(Operation::Move {
+                from: take_path(take("from")?)?,
+                path: take_path(take("path")?)?,
+                old: take("old")?,
+            }),
+            Value::Enum(op) => Err(DeserializationError::UnknownVariant {
+                variant: op.to_string(),
+                enum_name: "Operation".to_string(),
+            }),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "Enum".to_string(),
+                found: op.get_type(),
+            }),
+        };
+    }
+}
+
+impl Serialize for Patch {}
+impl ToSon for Patch {
+    fn to_son(&self) -> Value {
+        return self.operations.to_son();
+    }
+}
+
+impl Deserialize for Patch {}
+impl FromSon for Patch {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        let Value::Array(operations) = son else {
+            return Err(DeserializationError::UnexpectedType {
+                expected: "Array".to_string(),
+                found: son.get_type(),
+            });
+        };
+        return Ok(Patch {
+            operations: operations
+                .into_iter()
+                .map(Operation::from_son)
+                .collect::<Result<_, _>>()?,
+        });
+    }
+}
@@ -1,4 +1,7 @@
-use crate::token::{Token, TokenType};
+use crate::{
+    path::Path,
+    token::{Token, TokenType},
+};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug)]
@@ -101,6 +104,86 @@ impl Display for ParseError {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct QueryError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "Invalid query at position {}: {}", self.position, self.message);
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, PartialEq)]
+pub struct FlattenError {
+    pub key: String,
+    pub message: String,
+}
+
+impl Display for FlattenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "Invalid flattened key '{}': {}", self.key, self.message);
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+#[derive(Debug, PartialEq)]
+pub struct MismatchError {
+    pub path: Path,
+    pub message: String,
+}
+
+impl Display for MismatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "Values differ at {}: {}", self.path, self.message);
+    }
+}
+
+impl std::error::Error for MismatchError {}
+
+#[derive(Debug, PartialEq)]
+pub enum PatchError {
+    PathNotFound {
+        path: String,
+    },
+    PathExists {
+        path: String,
+    },
+    PreconditionFailed {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    InvalidTarget {
+        path: String,
+        message: String,
+    },
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            PatchError::PathNotFound { path } => write!(f, "Path not found: {}", path),
+            PatchError::PathExists { path } => write!(f, "Path already exists: {}", path),
+            PatchError::PreconditionFailed { path, expected, found } => {
+                write!(
+                    f,
+                    "Precondition failed at {}: expected {}, found {}",
+                    path, expected, found
+                )
+            }
+            PatchError::InvalidTarget { path, message } => write!(f, "Invalid target {}: {}", path, message),
+        };
+    }
+}
+
+impl std::error::Error for PatchError {}
+
 #[derive(Debug)]
 pub enum Error {
     ParseError(ParseError),
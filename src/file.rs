@@ -0,0 +1,119 @@
+use crate::{PrinterOptions, Value, printer::Printer, util};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// How `to_file_with` saves a value. `new()` uses the pretty layout, keeps the permissions of the file being replaced, and
+// doesn't keep a backup.
+#[derive(Debug, Clone)]
+pub struct FileOptions {
+    pub printer: PrinterOptions,
+    pub backup: bool,
+    pub preserve_permissions: bool,
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        return Self {
+            printer: PrinterOptions::new(),
+            backup: false,
+            preserve_permissions: true,
+        };
+    }
+}
+
+impl FileOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn printer(mut self, printer: PrinterOptions) -> Self {
+        self.printer = printer;
+        return self;
+    }
+
+    // Copies the file being replaced to `<name>.bak` next to it first, overwriting any older backup.
+    pub fn backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        return self;
+    }
+
+    // Gives the new file the permissions of the file it replaces. New files always get the default permissions.
+    pub fn preserve_permissions(mut self, preserve_permissions: bool) -> Self {
+        self.preserve_permissions = preserve_permissions;
+        return self;
+    }
+}
+
+// Distinguishes temporary files of concurrent writes from the same process.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+// Writes `value` to a temporary file in the same directory as `path`, flushes it to disk, and renames it over `path`.
+// Readers see either the old or the new file, never a partial one, and a failed write leaves the old file untouched.
+// A symlink at `path` is replaced rather than followed.
+pub fn write_atomic(path: &Path, value: &Value, options: &FileOptions) -> io::Result<()> {
+    let Some(name) = path.file_name() else {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' is not a file path", path.display()),
+        ));
+    };
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let previous = fs::metadata(path).ok().filter(|metadata| metadata.is_file());
+
+    let (temporary_path, mut file) = create_temporary(directory, name)?;
+    let written = (|| {
+        if options.preserve_permissions
+            && let Some(previous) = &previous
+        {
+            file.set_permissions(previous.permissions())?;
+        }
+        Printer::with_options(options.printer.clone()).write_value(&mut file, value)?;
+        file.sync_all()?;
+        if options.backup && previous.is_some() {
+            fs::copy(path, with_suffix(path, ".bak"))?;
+        }
+        return fs::rename(&temporary_path, path);
+    })();
+    if let Err(error) = written {
+        util::discard(fs::remove_file(&temporary_path));
+        return Err(error);
+    }
+
+    // Makes the rename itself durable. The new file is already in place, so a failure here is ignored rather than
+    // reported as a failed write. Directories can't be opened as files on every platform.
+    #[cfg(unix)]
+    util::discard(File::open(directory).and_then(|directory| directory.sync_all()));
+    return Ok(());
+}
+
+fn create_temporary(directory: &Path, name: &std::ffi::OsStr) -> io::Result<(PathBuf, File)> {
+    loop {
+        let mut temporary_name = OsString::from(".");
+        temporary_name.push(name);
+        temporary_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temporary_path = directory.join(temporary_name);
+        match OpenOptions::new().write(true).create_new(true).open(&temporary_path) {
+            Ok(file) => return Ok((temporary_path, file)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    return PathBuf::from(name);
+}
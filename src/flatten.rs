@@ -0,0 +1,306 @@
+use crate::{FlattenError, PathSegment, Value};
+use std::collections::{BTreeMap, HashMap};
+
+// Conversion between nested documents and flat `(key, value)` pairs such as `server.ports[0] = 8080`, for `.env`
+// files, metric labels or key-value stores.
+//
+// Keys join object keys with the separator and write array indices as `[i]`, or as plain segments when brackets are
+// disabled (`SERVER__PORTS__0`). A backslash escapes the next character, and is used for the separator, `[` and `\`
+// inside keys, and for object keys made only of digits when indices are plain segments. An empty key at the start is
+// marked with a leading separator (`{"": 1}` is `.`), so it isn't mistaken for a leaf root, whose key is empty. Empty
+// arrays and objects are kept as values so they survive a round trip.
+#[derive(Debug, Clone)]
+pub struct FlattenOptions {
+    pub separator: String,
+    pub brackets: bool,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        return Self {
+            separator: ".".to_string(),
+            brackets: true,
+        };
+    }
+}
+
+impl FlattenOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    // Must not be empty, which `flatten_with` and `unflatten_with` report as an error.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        return self;
+    }
+
+    // When disabled, array indices are written as separate segments: `ports.0` instead of `ports[0]`.
+    pub fn brackets(mut self, brackets: bool) -> Self {
+        self.brackets = brackets;
+        return self;
+    }
+
+    fn validate(&self) -> Result<(), FlattenError> {
+        if self.separator.is_empty() {
+            return Err(FlattenError {
+                key: String::new(),
+                message: "the separator can't be empty".to_string(),
+            });
+        }
+        return Ok(());
+    }
+}
+
+fn escape_key(key: &str, options: &FlattenOptions) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    if !options.brackets && !key.is_empty() && key.chars().all(|c| c.is_ascii_digit()) {
+        escaped.push('\\');
+    }
+    let mut rest = key;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with(&options.separator) {
+            for c in options.separator.chars() {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            rest = &rest[options.separator.len()..];
+            continue;
+        }
+        if c == '\\' || (options.brackets && c == '[') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    return escaped;
+}
+
+fn flatten_key(segments: &[PathSegment], options: &FlattenOptions) -> String {
+    let mut key = String::new();
+    if let Some(PathSegment::Key(first)) = segments.first()
+        && first.is_empty()
+    {
+        key.push_str(&options.separator);
+    }
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            PathSegment::Index(index) if options.brackets => key.push_str(&format!("[{}]", index)),
+            PathSegment::Index(index) => {
+                if i > 0 {
+                    key.push_str(&options.separator);
+                }
+                key.push_str(&index.to_string());
+            }
+            PathSegment::Key(k) => {
+                if i > 0 {
+                    key.push_str(&options.separator);
+                }
+                key.push_str(&escape_key(k, options));
+            }
+        }
+    }
+    return key;
+}
+
+fn parse_key(key: &str, options: &FlattenOptions) -> Result<Vec<PathSegment>, FlattenError> {
+    let error = |message: &str| FlattenError {
+        key: key.to_string(),
+        message: message.to_string(),
+    };
+    // Plain digit segments are indices when brackets are disabled, unless they contain escaped characters.
+    let finish = |current: String, escaped: bool| -> Result<PathSegment, FlattenError> {
+        if options.brackets || escaped || current.is_empty() || !current.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(PathSegment::Key(current));
+        }
+        return current
+            .parse::<usize>()
+            .map(PathSegment::Index)
+            .map_err(|_| error("array index is too large"));
+    };
+
+    let mut segments = Vec::new();
+    if key.is_empty() {
+        return Ok(segments);
+    }
+
+    let mut current = String::new();
+    let mut escaped = false;
+    // False right after an index, where only a separator, another index or the end may follow.
+    let mut in_key = true;
+    let mut rest = key;
+    if let Some(after) = key.strip_prefix(options.separator.as_str()) {
+        segments.push(PathSegment::Key(String::new()));
+        in_key = false;
+        rest = after;
+    }
+    while let Some(c) = rest.chars().next() {
+        if c == '\\' {
+            let Some(literal) = rest[1..].chars().next() else {
+                return Err(error("ends with an unfinished escape"));
+            };
+            if !in_key {
+                return Err(error("expected a separator after an index"));
+            }
+            current.push(literal);
+            escaped = true;
+            rest = &rest[1 + literal.len_utf8()..];
+        } else if rest.starts_with(&options.separator) {
+            if in_key {
+                segments.push(finish(std::mem::take(&mut current), escaped)?);
+            }
+            escaped = false;
+            in_key = true;
+            rest = &rest[options.separator.len()..];
+        } else if options.brackets && c == '[' {
+            // An index at the very start belongs to a root array, anywhere else it follows a key, which may be empty.
+            let at_start = rest.len() == key.len();
+            if in_key && !at_start {
+                segments.push(finish(std::mem::take(&mut current), escaped)?);
+            }
+            let Some(end) = rest.find(']') else {
+                return Err(error("unterminated array index"));
+            };
+            let index = &rest[1..end];
+            if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+                return Err(error("array indices must be non-negative integers"));
+            }
+            let index = index.parse::<usize>().map_err(|_| error("array index is too large"))?;
+            segments.push(PathSegment::Index(index));
+            escaped = false;
+            in_key = false;
+            rest = &rest[end + 1..];
+        } else {
+            if !in_key {
+                return Err(error("expected a separator after an index"));
+            }
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if in_key {
+        segments.push(finish(current, escaped)?);
+    }
+    return Ok(segments);
+}
+
+// A document being rebuilt, where arrays may still have gaps.
+enum Node {
+    Unset,
+    Leaf(Value),
+    Object(HashMap<String, Node>),
+    Array(BTreeMap<usize, Node>),
+}
+
+impl Node {
+    fn insert(&mut self, segments: &[PathSegment], value: Value, key: &str) -> Result<(), FlattenError> {
+        let conflict = || FlattenError {
+            key: key.to_string(),
+            message: "conflicts with another key".to_string(),
+        };
+        let Some((segment, rest)) = segments.split_first() else {
+            return match self {
+                Node::Unset => {
+                    *self = Node::Leaf(value);
+                    Ok(())
+                }
+                _ => Err(conflict()),
+            };
+        };
+
+        if let Node::Unset = self {
+            *self = match segment {
+                PathSegment::Key(_) => Node::Object(HashMap::new()),
+                PathSegment::Index(_) => Node::Array(BTreeMap::new()),
+            };
+        }
+        let child = match (self, segment) {
+            (Node::Object(object), PathSegment::Key(k)) => object.entry(k.clone()).or_insert(Node::Unset),
+            (Node::Array(array), PathSegment::Index(i)) => array.entry(*i).or_insert(Node::Unset),
+            _ => return Err(conflict()),
+        };
+        return child.insert(rest, value, key);
+    }
+
+    fn into_value(self, path: &mut Vec<PathSegment>, options: &FlattenOptions) -> Result<Value, FlattenError> {
+        return match self {
+            // Only the root can be unset, when there are no pairs at all.
+            Node::Unset => Ok(Value::Object(HashMap::new())),
+            Node::Leaf(value) => Ok(value),
+            Node::Object(object) => {
+                let mut result = HashMap::with_capacity(object.len());
+                for (key, node) in object {
+                    path.push(PathSegment::Key(key.clone()));
+                    let value = node.into_value(path, options)?;
+                    path.pop();
+                    result.insert(key, value);
+                }
+                Ok(Value::Object(result))
+            }
+            Node::Array(array) => {
+                let mut result = Vec::with_capacity(array.len());
+                for (index, node) in array {
+                    path.push(PathSegment::Index(result.len()));
+                    if index != result.len() {
+                        return Err(FlattenError {
+                            key: flatten_key(path, options),
+                            message: "is missing from the array".to_string(),
+                        });
+                    }
+                    let value = node.into_value(path, options)?;
+                    path.pop();
+                    result.push(value);
+                }
+                Ok(Value::Array(result))
+            }
+        };
+    }
+}
+
+fn flatten_pairs(value: &Value, options: &FlattenOptions) -> Vec<(String, Value)> {
+    return value
+        .iter_paths()
+        .filter(|(_, value)| {
+            return match value {
+                Value::Array(a) => a.is_empty(),
+                Value::Object(o) => o.is_empty(),
+                _ => true,
+            };
+        })
+        .map(|(path, value)| (flatten_key(path.segments(), options), value.clone()))
+        .collect();
+}
+
+impl Value {
+    pub fn flatten(&self) -> Vec<(String, Value)> {
+        return flatten_pairs(self, &FlattenOptions::default());
+    }
+
+    // Returns every leaf with its flattened key, in traversal order. A leaf root has an empty key.
+    pub fn flatten_with(&self, options: FlattenOptions) -> Result<Vec<(String, Value)>, FlattenError> {
+        options.validate()?;
+        return Ok(flatten_pairs(self, &options));
+    }
+
+    pub fn unflatten<I>(pairs: I) -> Result<Value, FlattenError>
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        return Value::unflatten_with(pairs, FlattenOptions::default());
+    }
+
+    // Rebuilds the nested document. Fails on malformed keys, keys that are set twice or used both as a value and as
+    // a container, and arrays with missing indices.
+    pub fn unflatten_with<I>(pairs: I, options: FlattenOptions) -> Result<Value, FlattenError>
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        options.validate()?;
+        let mut root = Node::Unset;
+        for (key, value) in pairs {
+            let segments = parse_key(&key, &options)?;
+            root.insert(&segments, value, &key)?;
+        }
+        return root.into_value(&mut Vec::new(), &options);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{lexer::SonLexer, token::TokenType, util};
+use std::io::{self, IsTerminal, Write};
+
+// Whether to colour output. `Auto` colours only when writing to a terminal and the `NO_COLOR` environment variable is
+// not set.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn should_color<W: IsTerminal>(&self, writer: &W) -> bool {
+        return match self {
+            ColorChoice::Auto => writer.is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        };
+    }
+}
+
+// The colours used for each kind of token, as ANSI SGR parameters such as `"32"` for green or `"1;34"` for bold blue.
+// An empty style leaves that kind of token uncoloured.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub key: String,
+    pub string: String,
+    pub number: String,
+    pub keyword: String,
+    pub enum_variant: String,
+    pub punctuation: String,
+    pub comment: String,
+    pub error: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        return Self {
+            key: "34".to_string(),
+            string: "32".to_string(),
+            number: "33".to_string(),
+            keyword: "35".to_string(),
+            enum_variant: "36".to_string(),
+            punctuation: "90".to_string(),
+            comment: "2;3".to_string(),
+            error: "1;31".to_string(),
+        };
+    }
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    // Object keys, bare or quoted.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = key.to_string();
+        return self;
+    }
+
+    // String, char and byte string literals.
+    pub fn string(mut self, string: &str) -> Self {
+        self.string = string.to_string();
+        return self;
+    }
+
+    // Numbers, including their sign, durations, sizes, and dates and times.
+    pub fn number(mut self, number: &str) -> Self {
+        self.number = number.to_string();
+        return self;
+    }
+
+    // `true`, `false` and `null`.
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.keyword = keyword.to_string();
+        return self;
+    }
+
+    pub fn enum_variant(mut self, enum_variant: &str) -> Self {
+        self.enum_variant = enum_variant.to_string();
+        return self;
+    }
+
+    // Brackets, commas and colons.
+    pub fn punctuation(mut self, punctuation: &str) -> Self {
+        self.punctuation = punctuation.to_string();
+        return self;
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment = comment.to_string();
+        return self;
+    }
+
+    // Text the lexer can't read, such as an unterminated string.
+    pub fn error(mut self, error: &str) -> Self {
+        self.error = error.to_string();
+        return self;
+    }
+
+    fn style_of(&self, token_type: &TokenType, is_key: bool) -> &str {
+        if is_key {
+            return &self.key;
+        }
+        return match token_type {
+            TokenType::StringLiteral | TokenType::CharLiteral | TokenType::BytesLiteral => &self.string,
+            TokenType::Negative
+            | TokenType::IntegerLiteral
+            | TokenType::FloatLiteral
+            | TokenType::QuantityLiteral
+            | TokenType::DateTimeLiteral => &self.number,
+            TokenType::True | TokenType::False | TokenType::Null => &self.keyword,
+            TokenType::Identifier | TokenType::QualifiedIdentifier => &self.enum_variant,
+            TokenType::Error => &self.error,
+            _ => &self.punctuation,
+        };
+    }
+}
+
+// Colours SON source text using the tokens from `SonLexer`. The text itself is unchanged, including whitespace and
+// comments, so this works on documents that don't parse: whatever the lexer can't read is marked with the error style.
+pub fn highlight_source(source: &str, theme: &Theme) -> String {
+    let mut output = Vec::with_capacity(source.len() * 2);
+    Highlighter::new(theme)
+        .write(&mut output, source, true)
+        .expect("writing to a Vec doesn't fail");
+    return String::from_utf8(output).expect("highlighting only adds ASCII to the source");
+}
+
+// Colours text that may arrive in pieces. The brackets that are still open carry over from one piece to the next, so
+// keys can be told from values: a key is followed by a `:`, or is inside an object and followed by `{` or `[`.
+struct Highlighter<'t> {
+    theme: &'t Theme,
+    brackets: Vec<TokenType>,
+}
+
+impl<'t> Highlighter<'t> {
+    fn new(theme: &'t Theme) -> Highlighter<'t> {
+        return Highlighter {
+            theme,
+            brackets: Vec::new(),
+        };
+    }
+
+    // Writes `source` coloured and returns how many of its bytes were written. Unless `complete`, more text follows, so
+    // the end of `source` is held back from the last token that can't be part of a longer one: a token after
+    // whitespace or a comment, a bracket or a comma. Everything before it lexes the same whatever comes next.
+    fn write<W: Write>(&mut self, output: &mut W, source: &str, complete: bool) -> io::Result<usize> {
+        let mut spans: Vec<(TokenType, usize, usize)> = Vec::new();
+        let mut lexer = SonLexer::new(source.as_bytes());
+        loop {
+            let token = lexer.next_token();
+            if token.get_type() == TokenType::EOF {
+                break;
+            }
+            spans.push((token.get_type(), lexer.current_offset(), lexer.current_end_offset()));
+        }
+
+        let count = match complete {
+            true => spans.len(),
+            false => (1..spans.len())
+                .rev()
+                .find(|&i| spans[i].1 > spans[i - 1].2 || is_delimiter(&spans[i].0))
+                .unwrap_or(0),
+        };
+        let mut position = 0;
+        for i in 0..count {
+            let (token_type, start, end) = &spans[i];
+            write_gap(output, &source[position..*start], self.theme)?;
+            let next = spans.get(i + 1).map(|(next, _, _)| next);
+            let is_key = match next {
+                Some(TokenType::Colon) => true,
+                Some(TokenType::LeftCurlyBrace | TokenType::LeftSquareBrace) => {
+                    matches!(token_type, TokenType::Identifier | TokenType::StringLiteral)
+                        && self.brackets.last() == Some(&TokenType::LeftCurlyBrace)
+                }
+                _ => false,
+            };
+            write_styled(output, &source[*start..*end], self.theme.style_of(token_type, is_key))?;
+            match token_type {
+                TokenType::LeftCurlyBrace | TokenType::LeftSquareBrace => self.brackets.push(token_type.clone()),
+                TokenType::RightCurlyBrace | TokenType::RightSquareBrace => util::discard(self.brackets.pop()),
+                _ => {}
+            }
+            position = *end;
+        }
+        if complete {
+            write_gap(output, &source[position..], self.theme)?;
+            position = source.len();
+        }
+        return Ok(position);
+    }
+}
+
+fn is_delimiter(token_type: &TokenType) -> bool {
+    return matches!(
+        token_type,
+        TokenType::LeftCurlyBrace
+            | TokenType::RightCurlyBrace
+            | TokenType::LeftSquareBrace
+            | TokenType::RightSquareBrace
+            | TokenType::Comma
+    );
+}
+
+// Colours SON text as it's written to `inner`, holding back only the end of what was written so far, see
+// `Highlighter::write`. `finish` writes the rest.
+pub(crate) struct HighlightWriter<'t, W: Write> {
+    inner: W,
+    highlighter: Highlighter<'t>,
+    pending: Vec<u8>,
+}
+
+impl<'t, W: Write> HighlightWriter<'t, W> {
+    pub(crate) fn new(inner: W, theme: &'t Theme) -> HighlightWriter<'t, W> {
+        return HighlightWriter {
+            inner,
+            highlighter: Highlighter::new(theme),
+            pending: Vec::new(),
+        };
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        let source = String::from_utf8_lossy(&self.pending);
+        self.highlighter.write(&mut self.inner, &source, true)?;
+        self.inner.flush()?;
+        return Ok(self.inner);
+    }
+}
+
+impl<W: Write> Write for HighlightWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // A character may be split between two writes.
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(source) => source.len(),
+            Err(error) => error.valid_up_to(),
+        };
+        let source = std::str::from_utf8(&self.pending[..valid]).expect("checked above");
+        let written = self.highlighter.write(&mut self.inner, source, false)?;
+        self.pending.drain(..written);
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.inner.flush();
+    }
+}
+
+// Writes the whitespace and comments between two tokens.
+fn write_gap<W: Write>(output: &mut W, mut gap: &str, theme: &Theme) -> io::Result<()> {
+    while let Some(start) = gap.find('/') {
+        output.write_all(&gap.as_bytes()[..start])?;
+        let comment = &gap[start..];
+        let length = if let Some(body) = comment.strip_prefix("/*") {
+            body.find("*/").map_or(comment.len(), |end| end + 4)
+        } else {
+            comment.find('\n').unwrap_or(comment.len())
+        };
+        write_styled(output, &comment[..length], &theme.comment)?;
+        gap = &comment[length..];
+    }
+    return output.write_all(gap.as_bytes());
+}
+
+fn write_styled<W: Write>(output: &mut W, text: &str, style: &str) -> io::Result<()> {
+    if style.is_empty() {
+        return output.write_all(text.as_bytes());
+    }
+    return write!(output, "\x1b[{}m{}\x1b[0m", style, text);
+}
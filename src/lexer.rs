@@ -1,6 +1,7 @@
 use crate::{
-    token::{Token, TokenType},
-    util,
+    DateTime, bytes,
+    token::{self, Token, TokenType},
+    units, util,
 };
 use std::{
     collections::VecDeque,
@@ -12,6 +13,23 @@ impl Keywords {
     const TRUE: [char; 4] = ['t', 'r', 'u', 'e'];
     const FALSE: [char; 5] = ['f', 'a', 'l', 's', 'e'];
     const NULL: [char; 4] = ['n', 'u', 'l', 'l'];
+    const INFINITY: [char; 3] = ['i', 'n', 'f'];
+    const NAN: [char; 3] = ['N', 'a', 'N'];
+    const BYTES: [char; 1] = ['b'];
+    const BASE64: [char; 3] = ['b', '6', '4'];
+}
+
+// Words that read as values rather than identifiers, so they can't be bare keys or unqualified enum variants.
+pub(crate) fn is_keyword(text: &str) -> bool {
+    return matches!(text, "true" | "false" | "null" | "inf" | "NaN");
+}
+
+// Whether `text` can be written as a bare object key: an identifier that isn't a keyword.
+pub(crate) fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    return is_identifier && !is_keyword(text);
 }
 
 const TOKENIZER_BUFFER_SIZE: usize = 1024;
@@ -27,6 +45,12 @@ where
     leftovers: Vec<u8>,
     current_token_source: Vec<char>,
     current_token_col: u32,
+    // Byte offsets into the input, of the next character and of the start of the current token.
+    offset: usize,
+    current_token_offset: usize,
+    // The source text consumed since `start_recording`.
+    recording: Option<String>,
+    skip_recorded_whitespace: bool,
 
     current_token: Option<Token>,
     previous_token: Option<Token>,
@@ -50,6 +74,10 @@ where
             leftovers: Vec::new(),
             current_token_source: Vec::new(),
             current_token_col: 0,
+            offset: 0,
+            current_token_offset: 0,
+            recording: None,
+            skip_recorded_whitespace: false,
 
             current_token: None,
             previous_token: None,
@@ -59,6 +87,13 @@ where
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
         self.current_token_col = self.col;
+        self.current_token_offset = self.offset;
+        if self.skip_recorded_whitespace {
+            if let Some(recording) = self.recording.as_mut() {
+                recording.clear();
+            }
+            self.skip_recorded_whitespace = false;
+        }
 
         let Some(c) = self.advance() else {
             return self.new_token(TokenType::EOF);
@@ -91,6 +126,35 @@ where
     pub fn current(&self) -> Option<Token> {
         return self.current_token.clone();
     }
+
+    // Starts copying the consumed source text, beginning with `prefix`. With an empty prefix, whitespace and comments
+    // before the next token are left out.
+    pub fn start_recording(&mut self, prefix: &str) {
+        self.recording = Some(prefix.to_string());
+        self.skip_recorded_whitespace = prefix.is_empty();
+    }
+
+    pub fn is_recording(&self) -> bool {
+        return self.recording.is_some();
+    }
+
+    // Returns the text consumed since `start_recording`, up to the end of the last token.
+    pub fn stop_recording(&mut self) -> String {
+        self.skip_recorded_whitespace = false;
+        return self.recording.take().unwrap_or_default();
+    }
+
+    // The byte offset in the input where the most recent token starts. Token sources are copied verbatim from the
+    // input, so `offset..offset + source.len()` is the token's span.
+    pub fn current_offset(&self) -> usize {
+        return self.current_token_offset;
+    }
+
+    // The byte offset in the input just after the most recent token. Unlike `source.len()`, this also gives the span of
+    // error tokens, whose source is the error message.
+    pub fn current_end_offset(&self) -> usize {
+        return self.offset;
+    }
 }
 
 impl<T> Iterator for SonLexer<T>
@@ -164,19 +228,30 @@ where
 
     // Check next character but don't consume.
     fn peek_next(&mut self) -> Option<char> {
-        if !self.current_chunk.len() >= 2 || self.next_chunk() {
-            return Some(self.current_chunk[1]);
+        return self.peek_at(1);
+    }
+
+    // Check the character `n` positions ahead but don't consume.
+    fn peek_at(&mut self, n: usize) -> Option<char> {
+        while self.current_chunk.len() <= n {
+            if !self.next_chunk() {
+                return None;
+            }
         }
 
-        return None;
+        return Some(self.current_chunk[n]);
     }
 
     // Get the current character and advance.
     fn advance(&mut self) -> Option<char> {
         if !self.current_chunk.is_empty() || self.next_chunk() {
             let c = self.current_chunk.pop_front().unwrap();
-            self.current_token_source.push(c.clone());
+            self.current_token_source.push(c);
             self.col += 1;
+            self.offset += c.len_utf8();
+            if let Some(recording) = self.recording.as_mut() {
+                recording.push(c);
+            }
             return Some(c);
         }
 
@@ -244,6 +319,20 @@ where
     }
 
     fn consume_string_literal(&mut self) -> Token {
+        if !self.consume_quoted() {
+            return self.new_error_token("Unterminated string literal");
+        }
+        let source: String = self.current_token_source.iter().collect();
+        if token::unescape(&source[1..source.len() - 1]).is_none() {
+            self.current_token_source.clear();
+            return self.new_error_token("Invalid escape sequence in string literal");
+        }
+        return self.new_token(TokenType::StringLiteral);
+    }
+
+    // Consumes the rest of a quoted literal whose opening quote was already consumed.
+    // Returns false if the closing quote is missing.
+    fn consume_quoted(&mut self) -> bool {
         // Advance until the end of the string literal.
         while let Some(c) = self.peek() {
             match c {
@@ -254,23 +343,41 @@ where
             }
         }
         // Validate closing quotes.
-        if !self.match_token('"') {
-            return self.new_error_token("Unterminated string literal");
+        return self.match_token('"');
+    }
+
+    // Byte string literals are a `b` or `b64` prefix directly followed by a quoted string.
+    fn consume_bytes_literal(&mut self) -> Token {
+        self.advance();
+        if !self.consume_quoted() {
+            return self.new_error_token("Unterminated byte string literal");
         }
-        return self.new_token(TokenType::StringLiteral);
+        let source: String = self.current_token_source.iter().collect();
+        if bytes::decode_literal(&source).is_none() {
+            self.current_token_source.clear();
+            return self.new_error_token("Invalid byte string literal");
+        }
+        return self.new_token(TokenType::BytesLiteral);
     }
 
     fn consume_char_literal(&mut self) -> Token {
-        // Advance one char handling escaping sequences
-        if let Some(char) = self.advance() {
-            if char == '\\' {
+        // Advance one char handling escaping sequences, `\u{..}` runs until the closing brace.
+        if self.advance() == Some('\\') && self.advance() == Some('u') && self.peek() == Some('{') {
+            while self.peek().is_some_and(|c| c != '}' && c != '\'' && c != '\n') {
                 self.advance();
             }
+            self.match_token('}');
         }
         // Validate closing quote
         if !self.match_token('\'') {
             return self.new_error_token("Unterminated char literal");
         }
+        let source: String = self.current_token_source.iter().collect();
+        let is_single_char = token::unescape(&source[1..source.len() - 1]).is_some_and(|c| c.chars().count() == 1);
+        if !is_single_char {
+            self.current_token_source.clear();
+            return self.new_error_token("Invalid char literal");
+        }
         return self.new_token(TokenType::CharLiteral);
     }
 
@@ -280,17 +387,77 @@ where
             self.advance();
         }
 
+        // A four digit year followed by a dash starts a date, two digits followed by a colon start a time.
+        let digits = self.current_token_source.len();
+        if (digits == 4 && self.peek() == Some('-')) || (digits == 2 && self.peek() == Some(':')) {
+            return self.consume_datetime_literal();
+        }
+
         // Look for a fractional part.
-        if !self.match_token('.') {
-            return self.new_token(TokenType::IntegerLiteral);
+        let mut is_float = self.match_token('.');
+        if is_float {
+            // Advance until the end of the fractional part.
+            while self.peek().is_some_and(|c| c.is_numeric()) {
+                self.advance();
+            }
         }
 
-        // Advance until the end of the fractional part.
-        while self.peek().is_some_and(|c| c.is_numeric()) {
+        // Look for an exponent such as `e-7`. A letter that isn't followed by digits is a unit suffix instead.
+        let exponent_digit = match self.peek_next() {
+            Some('+' | '-') => 2,
+            _ => 1,
+        };
+        if self.peek().is_some_and(|c| c == 'e' || c == 'E')
+            && self.peek_at(exponent_digit).is_some_and(|c| c.is_ascii_digit())
+        {
+            self.advance_multiple(exponent_digit as u64);
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.advance();
+            }
+            is_float = true;
+        }
+
+        // A letter directly after the number starts a unit suffix.
+        if self.peek().is_some_and(|c| c.is_alphabetic()) {
+            return self.consume_quantity_literal();
+        }
+
+        return self.new_token(if is_float {
+            TokenType::FloatLiteral
+        } else {
+            TokenType::IntegerLiteral
+        });
+    }
+
+    // Consumes the rest of a duration or size such as `1h30m` or `64KiB` and checks its units.
+    fn consume_quantity_literal(&mut self) -> Token {
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '.') {
             self.advance();
         }
 
-        return self.new_token(TokenType::FloatLiteral);
+        let source: String = self.current_token_source.iter().collect();
+        if let Err(e) = units::parse_quantity(&source) {
+            self.current_token_source.clear();
+            return self.new_error_token(&format!("Invalid quantity literal '{}': {}", source, e));
+        }
+        return self.new_token(TokenType::QuantityLiteral);
+    }
+
+    // Consumes the rest of a date or time and validates it, including the calendar.
+    fn consume_datetime_literal(&mut self) -> Token {
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | ':' | '.' | '+' | 'T' | 't' | 'Z' | 'z'))
+        {
+            self.advance();
+        }
+
+        let source: String = self.current_token_source.iter().collect();
+        if let Err(e) = source.parse::<DateTime>() {
+            self.current_token_source.clear();
+            return self.new_error_token(&format!("Invalid date or time literal '{}': {}", source, e));
+        }
+        return self.new_token(TokenType::DateTimeLiteral);
     }
 
     // This method requires all the characters from the identifier to have been consumed already.
@@ -300,6 +467,8 @@ where
             't' if self.current_token_source == Keywords::TRUE => return TokenType::True,
             'f' if self.current_token_source == Keywords::FALSE => return TokenType::False,
             'n' if self.current_token_source == Keywords::NULL => return TokenType::Null,
+            'i' if self.current_token_source == Keywords::INFINITY => return TokenType::FloatLiteral,
+            'N' if self.current_token_source == Keywords::NAN => return TokenType::FloatLiteral,
             _ => (),
         }
 
@@ -308,11 +477,42 @@ where
 
     fn consume_identifier(&mut self) -> Token {
         // Consume all characters available for the identifier.
-        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+        self.consume_identifier_chars();
+
+        let is_bytes_prefix =
+            self.current_token_source == Keywords::BYTES || self.current_token_source == Keywords::BASE64;
+        if is_bytes_prefix && self.peek() == Some('"') {
+            return self.consume_bytes_literal();
+        }
+
+        // A qualified enum variant: `Status.Active` or `module::Status::Active`.
+        let is_identifier_start = |c: char| c.is_ascii_alphabetic() || c == '_';
+        let mut is_qualified = false;
+        if self.peek() == Some('.') && self.peek_next().is_some_and(is_identifier_start) {
             self.advance();
+            self.consume_identifier_chars();
+            is_qualified = true;
+        } else {
+            while self.peek() == Some(':')
+                && self.peek_next() == Some(':')
+                && self.peek_at(2).is_some_and(is_identifier_start)
+            {
+                self.advance_multiple(2);
+                self.consume_identifier_chars();
+                is_qualified = true;
+            }
+        }
+        if is_qualified {
+            return self.new_token(TokenType::QualifiedIdentifier);
         }
 
         let identifier_type = self.get_identifier_type();
         return self.new_token(identifier_type);
     }
+
+    fn consume_identifier_chars(&mut self) {
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.advance();
+        }
+    }
 }
@@ -1,29 +1,81 @@
+pub mod approx;
+pub mod bigint;
+pub mod borrowed;
+pub mod bytes;
+pub mod canonical;
+pub mod compact;
+mod compare;
+pub mod datetime;
 pub mod deserialize;
+pub mod diff;
 pub mod error;
+pub mod file;
+pub mod flatten;
+pub mod highlight;
 pub mod lexer;
+pub mod merge;
+pub mod number;
 pub mod parser;
+pub mod path;
 pub mod printer;
+pub mod query;
+pub mod raw;
+pub mod schema;
 pub mod serialize;
 pub mod token;
+pub mod units;
 pub mod util;
 pub mod value;
+pub mod variant;
 
 pub use crate::{
+    approx::Tolerance,
+    bigint::BigInt,
+    borrowed::{BorrowedParser, BorrowedValue},
+    bytes::Bytes,
+    compact::{CompactDocument, CompactValue},
+    datetime::{Date, DateTime, Offset, Time},
     deserialize::{Deserialize, FromSon},
-    error::{DeserializationError, Error, ParseError},
+    diff::{Operation, Patch, apply_patch, diff},
+    error::{DeserializationError, Error, FlattenError, MismatchError, ParseError, PatchError, QueryError},
+    file::FileOptions,
+    flatten::FlattenOptions,
+    highlight::{ColorChoice, Theme},
+    merge::{ArrayMergeStrategy, MergeOptions, MergeReport},
+    number::Number,
+    parser::ParseOptions,
+    path::{Path, PathSegment},
+    printer::{Commas, LineEnding, PrinterOptions, Wrapping},
+    query::Query,
+    raw::RawSon,
+    schema::{Field, Schema},
     serialize::{Serialize, ToSon},
-    value::Value,
+    units::ByteSize,
+    value::{PathIter, Value, Walk},
+    variant::EnumVariant,
 };
-use crate::{parser::SonParser, printer::Printer};
-pub use son_macros::{Deserialize, Serialize};
-use std::fs::File;
+use crate::{highlight::HighlightWriter, parser::SonParser, printer::Printer};
+pub use son_macros::{Deserialize, Serialize, son};
+use std::{
+    fs::File,
+    io::{BufWriter, IsTerminal, Write},
+};
+
+// Adds the paths of `T`'s `RawSon` fields, so the parser keeps their source text.
+fn raw_paths_of<T>(mut options: ParseOptions) -> ParseOptions
+where
+    T: Deserialize,
+{
+    options.raw_paths.extend(T::raw_paths());
+    return options;
+}
 
 pub fn from_file<T>(file_path: &str) -> Result<T, Error>
 where
     T: Deserialize,
 {
     let file = File::open(file_path)?;
-    let mut parser = SonParser::new(file);
+    let mut parser = SonParser::with_options(file, raw_paths_of::<T>(ParseOptions::default()));
     let son = parser.parse()?;
     return Ok(T::from_son(son)?);
 }
@@ -32,7 +84,32 @@ pub fn from_str<T>(str: &str) -> Result<T, Error>
 where
     T: Deserialize,
 {
-    let mut parser = SonParser::new(str.as_bytes());
+    let mut parser = SonParser::with_options(str.as_bytes(), raw_paths_of::<T>(ParseOptions::default()));
+    let son = parser.parse()?;
+    return Ok(T::from_son(son)?);
+}
+
+// Parses without copying strings, enum names or object keys out of `str`, see `BorrowedValue`.
+pub fn from_str_borrowed(str: &str) -> Result<BorrowedValue<'_>, Error> {
+    let mut parser = BorrowedParser::new(str);
+    return Ok(parser.parse()?);
+}
+
+pub fn from_file_with<T>(file_path: &str, options: ParseOptions) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    let file = File::open(file_path)?;
+    let mut parser = SonParser::with_options(file, raw_paths_of::<T>(options));
+    let son = parser.parse()?;
+    return Ok(T::from_son(son)?);
+}
+
+pub fn from_str_with<T>(str: &str, options: ParseOptions) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    let mut parser = SonParser::with_options(str.as_bytes(), raw_paths_of::<T>(options));
     let son = parser.parse()?;
     return Ok(T::from_son(son)?);
 }
@@ -44,18 +121,120 @@ where
     return T::to_son(value);
 }
 
+// A single line with commas and no optional whitespace, see `PrinterOptions::compact`.
 pub fn to_string<T>(value: &T) -> String
 where
     T: Serialize,
 {
-    return to_son(value).to_string();
+    return to_string_with(value, &PrinterOptions::compact());
+}
+
+// A single-line form with sorted keys and normalized numbers, see `canonical.rs`.
+pub fn to_canonical_string<T>(value: &T) -> String
+where
+    T: Serialize,
+{
+    return canonical::to_canonical_string(&to_son(value));
 }
 
 pub fn to_string_pretty<T>(value: &T, indentation: &str) -> String
 where
     T: Serialize,
 {
-    let son = to_son(value);
-    let printer = Printer::new(indentation.to_string());
-    return printer.son_to_string(&son);
+    return to_string_with(value, &PrinterOptions::new().indent(indentation));
+}
+
+pub fn to_string_with<T>(value: &T, options: &PrinterOptions) -> String
+where
+    T: Serialize,
+{
+    let printer = Printer::with_options(options.clone());
+    return printer.son_to_string(&to_son(value));
+}
+
+// Writes the same text as `to_string` without building it in memory first.
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    return to_writer_with(writer, value, &PrinterOptions::compact());
+}
+
+pub fn to_writer_pretty<W, T>(writer: W, value: &T, indentation: &str) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    return to_writer_with(writer, value, &PrinterOptions::new().indent(indentation));
+}
+
+pub fn to_writer_with<W, T>(mut writer: W, value: &T, options: &PrinterOptions) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let printer = Printer::with_options(options.clone());
+    printer.write_value(&mut writer, &to_son(value))?;
+    return Ok(());
+}
+
+// Saves the same text as `to_string`, replacing the file atomically, see `file::write_atomic`.
+pub fn to_file<P, T>(path: P, value: &T) -> Result<(), Error>
+where
+    P: AsRef<std::path::Path>,
+    T: Serialize,
+{
+    return to_file_with(path, value, &FileOptions::new().printer(PrinterOptions::compact()));
+}
+
+pub fn to_file_pretty<P, T>(path: P, value: &T, indentation: &str) -> Result<(), Error>
+where
+    P: AsRef<std::path::Path>,
+    T: Serialize,
+{
+    return to_file_with(
+        path,
+        value,
+        &FileOptions::new().printer(PrinterOptions::new().indent(indentation)),
+    );
+}
+
+pub fn to_file_with<P, T>(path: P, value: &T, options: &FileOptions) -> Result<(), Error>
+where
+    P: AsRef<std::path::Path>,
+    T: Serialize,
+{
+    file::write_atomic(path.as_ref(), &to_son(value), options)?;
+    return Ok(());
+}
+
+// The same text as `to_string_with`, coloured with ANSI escape codes, see `highlight.rs`.
+pub fn to_string_highlighted<T>(value: &T, options: &PrinterOptions, theme: &Theme) -> String
+where
+    T: Serialize,
+{
+    return highlight::highlight_source(&to_string_with(value, options), theme);
+}
+
+// Writes the same text as `to_writer_with`, coloured unless `color` says otherwise for this writer. The text is
+// coloured as it's printed rather than built in memory first.
+pub fn to_writer_highlighted<W, T>(
+    writer: W,
+    value: &T,
+    options: &PrinterOptions,
+    theme: &Theme,
+    color: ColorChoice,
+) -> Result<(), Error>
+where
+    W: Write + IsTerminal,
+    T: Serialize,
+{
+    if !color.should_color(&writer) {
+        return to_writer_with(writer, value, options);
+    }
+    let mut highlighted = HighlightWriter::new(BufWriter::new(writer), theme);
+    Printer::with_options(options.clone()).write_value(&mut highlighted, &to_son(value))?;
+    highlighted.finish()?;
+    return Ok(());
 }
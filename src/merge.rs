@@ -0,0 +1,162 @@
+use crate::{path::Path, value::Value};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ArrayMergeStrategy {
+    // The array from the override replaces the base array.
+    #[default]
+    Replace,
+    // Elements from the override are appended after the base elements.
+    Append,
+    // Elements at the same position are merged, extra override elements are appended.
+    MergeByIndex,
+    // Object elements sharing the same value for the given field are merged, the others are appended.
+    MergeByKey(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    pub arrays: ArrayMergeStrategy,
+    pub null_deletes: bool,
+}
+
+impl MergeOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn arrays(mut self, strategy: ArrayMergeStrategy) -> Self {
+        self.arrays = strategy;
+        return self;
+    }
+
+    // When enabled, a `null` in the override removes the key from the base object instead of setting it to `null`.
+    // Values that the override adds or replaces as a whole don't keep such keys either.
+    pub fn null_deletes(mut self, null_deletes: bool) -> Self {
+        self.null_deletes = null_deletes;
+        return self;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub overridden: Vec<Path>,
+    pub added: Vec<Path>,
+    pub removed: Vec<Path>,
+}
+
+impl MergeReport {
+    pub fn is_unchanged(&self) -> bool {
+        return self.overridden.is_empty() && self.added.is_empty() && self.removed.is_empty();
+    }
+}
+
+impl Value {
+    // Recursively merges `other` into `self`. Objects are merged key by key, arrays according to
+    // `options.arrays`, and any other value from `other` replaces the one in `self`.
+    pub fn merge(&mut self, other: Value, options: MergeOptions) -> MergeReport {
+        let mut report = MergeReport::default();
+        merge_inner(self, other, &Path::root(), &options, &mut report);
+        return report;
+    }
+}
+
+fn merge_inner(target: &mut Value, other: Value, path: &Path, options: &MergeOptions, report: &mut MergeReport) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            let mut entries: Vec<(String, Value)> = other.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (key, value) in entries {
+                let key_path = path.key(&key);
+                if options.null_deletes && value == Value::Null {
+                    if target.remove(&key).is_some() {
+                        report.removed.push(key_path);
+                    }
+                    continue;
+                }
+
+                match target.get_mut(&key) {
+                    Some(existing) => merge_inner(existing, value, &key_path, options, report),
+                    None => {
+                        target.insert(key, incoming(value, options));
+                        report.added.push(key_path);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(other)) => match &options.arrays {
+            ArrayMergeStrategy::Replace => {
+                let other: Vec<Value> = other.into_iter().map(|value| incoming(value, options)).collect();
+                if *target != other {
+                    *target = other;
+                    report.overridden.push(path.clone());
+                }
+            }
+            ArrayMergeStrategy::Append => {
+                for value in other {
+                    report.added.push(path.index(target.len()));
+                    target.push(incoming(value, options));
+                }
+            }
+            ArrayMergeStrategy::MergeByIndex => {
+                for (index, value) in other.into_iter().enumerate() {
+                    match target.get_mut(index) {
+                        Some(existing) => merge_inner(existing, value, &path.index(index), options, report),
+                        None => {
+                            report.added.push(path.index(target.len()));
+                            target.push(incoming(value, options));
+                        }
+                    }
+                }
+            }
+            ArrayMergeStrategy::MergeByKey(field) => {
+                for value in other {
+                    let position = match &value {
+                        Value::Object(o) => o.get(field).and_then(|key| {
+                            target.iter().position(|existing| match existing {
+                                Value::Object(e) => e.get(field) == Some(key),
+                                _ => false,
+                            })
+                        }),
+                        _ => None,
+                    };
+
+                    match position {
+                        Some(index) => merge_inner(&mut target[index], value, &path.index(index), options, report),
+                        None => {
+                            report.added.push(path.index(target.len()));
+                            target.push(incoming(value, options));
+                        }
+                    }
+                }
+            }
+        },
+        (target, other) => {
+            let other = incoming(other, options);
+            if *target != other {
+                *target = other;
+                report.overridden.push(path.clone());
+            }
+        }
+    }
+}
+
+// A value from the override that is taken as a whole rather than merged, without the keys its `null`s would delete.
+fn incoming(mut value: Value, options: &MergeOptions) -> Value {
+    if options.null_deletes {
+        remove_nulls(&mut value);
+    }
+    return value;
+}
+
+// Removes `null` object entries at any depth. Array elements are kept, as their position is part of the value.
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|_, value| *value != Value::Null);
+            object.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(array) => array.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
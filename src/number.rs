@@ -0,0 +1,144 @@
+use crate::{BigInt, DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+// A numeric literal kept exactly as it was written, produced by the parser when `preserve_numbers` is enabled.
+// Numbers compare by their literal text, so `19.10` and `19.1` are different numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Number {
+    literal: String,
+}
+
+impl Number {
+    // Accepts an optional minus sign, digits, and an optional fractional part: `42`, `-0.5`, `19.10`.
+    pub fn new(literal: &str) -> Option<Number> {
+        let unsigned = literal.strip_prefix('-').unwrap_or(literal);
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (unsigned, ""),
+        };
+        if integer.is_empty()
+            || !integer.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        return Some(Number {
+            literal: literal.to_string(),
+        });
+    }
+
+    pub(crate) fn heap_size(&self) -> usize {
+        return self.literal.capacity();
+    }
+
+    pub fn as_str(&self) -> &str {
+        return &self.literal;
+    }
+
+    pub fn is_negative(&self) -> bool {
+        return self.literal.starts_with('-');
+    }
+
+    pub fn has_fraction(&self) -> bool {
+        return self.literal.contains('.');
+    }
+
+    pub fn negate(&self) -> Number {
+        let literal = match self.literal.strip_prefix('-') {
+            Some(unsigned) => unsigned.to_string(),
+            None => format!("-{}", self.literal),
+        };
+        return Number { literal };
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        return self.literal.parse::<f64>().unwrap();
+    }
+
+    // The exact integer value, if the number has no non-zero fractional digits.
+    pub fn to_big_integer(&self) -> Option<BigInt> {
+        let (integer, fraction) = match self.literal.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (self.literal.as_str(), ""),
+        };
+        if fraction.chars().any(|c| c != '0') {
+            return None;
+        }
+        return integer.parse::<BigInt>().ok();
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        return self.to_big_integer()?.to_i128();
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        return self.to_big_integer()?.to_u128();
+    }
+
+    // Parsing hook for user-supplied numeric types such as fixed-point decimals.
+    pub fn parse<T>(&self) -> Result<T, T::Err>
+    where
+        T: FromStr,
+    {
+        return self.literal.parse::<T>();
+    }
+
+    // Converts into an integer type, failing instead of truncating or wrapping.
+    pub fn to_integer<T>(&self) -> Result<T, DeserializationError>
+    where
+        T: TryFrom<i128>,
+    {
+        return self
+            .to_i128()
+            .and_then(|i| T::try_from(i).ok())
+            .ok_or_else(|| DeserializationError::InvalidValue {
+                message: format!(
+                    "{} is not representable as {}",
+                    self.literal,
+                    std::any::type_name::<T>()
+                ),
+            });
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.literal);
+    }
+}
+
+impl FromStr for Number {
+    type Err = DeserializationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Number::new(s).ok_or_else(|| DeserializationError::InvalidValue {
+            message: format!("'{}' is not a number", s),
+        });
+    }
+}
+
+impl Serialize for Number {}
+impl ToSon for Number {
+    fn to_son(&self) -> Value {
+        return Value::Number(self.clone());
+    }
+}
+
+impl Deserialize for Number {}
+impl FromSon for Number {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::Number(n) => Ok(n),
+            Value::Integer(i) => Ok(Number { literal: i.to_string() }),
+            Value::BigInteger(b) => Ok(Number { literal: b.to_string() }),
+            Value::Float(f) if f.is_finite() => Ok(Number { literal: f.to_string() }),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "Number".to_string(),
+                found: son.get_type(),
+            }),
+        };
+    }
+}
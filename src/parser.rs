@@ -2,16 +2,117 @@ use crate::{
     ParseError::UnexpectedToken,
     error::{ParseError, ParseStep},
     lexer::SonLexer,
-    token::TokenType,
+    number::Number,
+    path::{Path, PathSegment},
+    raw::RawSon,
+    token::{Token, TokenType},
     value::Value,
+    variant::EnumVariant,
 };
-use std::{collections::HashMap, io::Read};
+use std::{collections::HashMap, hash::Hash, io::Read};
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub preserve_numbers: bool,
+    pub raw_paths: Vec<Path>,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    // Keep numeric literals as `Value::Number` with their exact source text instead of converting them
+    // to `Integer` or `Float`.
+    pub fn preserve_numbers(mut self, preserve_numbers: bool) -> Self {
+        self.preserve_numbers = preserve_numbers;
+        return self;
+    }
+
+    // Keep the source text of the values at these paths as `Value::Raw`. Deserializing with `from_str` and friends
+    // adds the paths of the target type's `RawSon` fields.
+    pub fn raw_paths(mut self, raw_paths: Vec<Path>) -> Self {
+        self.raw_paths = raw_paths;
+        return self;
+    }
+}
+
+// The tokens that may follow a `-`. Durations, sizes and dates can't be negative, so `-30s` is an error rather than
+// `30s`.
+pub(crate) const NEGATABLE_TOKENS: &[TokenType] = &[
+    TokenType::Negative,
+    TokenType::True,
+    TokenType::False,
+    TokenType::IntegerLiteral,
+    TokenType::FloatLiteral,
+];
+
+// Builds the values the grammar reads, so `SonParser` and `BorrowedParser` share one grammar while producing different
+// value types. Tokens come with the offset of their source text in the input.
+pub(crate) trait ValueBuilder {
+    type Value;
+    type Key: Eq + Hash + AsRef<str>;
+
+    // Every value except strings, enums, arrays and objects.
+    fn scalar(&mut self, value: Value) -> Self::Value;
+    fn string(&mut self, token: &Token, offset: usize) -> Self::Value;
+    fn enum_variant(&mut self, token: &Token, offset: usize) -> Self::Value;
+    // The name of an object field, written as an identifier or a string.
+    fn key(&mut self, token: &Token, offset: usize) -> Self::Key;
+    fn negate(&mut self, value: Self::Value) -> Self::Value;
+    fn is_null(&self, value: &Self::Value) -> bool;
+    fn array(&mut self, items: Vec<Self::Value>) -> Self::Value;
+    fn object(&mut self, fields: HashMap<Self::Key, Self::Value>) -> Self::Value;
+}
+
+// Builds `Value`s.
+pub(crate) struct OwnedBuilder;
+
+impl ValueBuilder for OwnedBuilder {
+    type Value = Value;
+    type Key = String;
+
+    fn scalar(&mut self, value: Value) -> Value {
+        return value;
+    }
+
+    fn string(&mut self, token: &Token, _offset: usize) -> Value {
+        return token.get_value().unwrap();
+    }
+
+    fn enum_variant(&mut self, token: &Token, _offset: usize) -> Value {
+        return Value::Enum(EnumVariant::from_source(&token.get_source()));
+    }
+
+    fn key(&mut self, token: &Token, _offset: usize) -> String {
+        return match (token.get_type(), token.get_value()) {
+            (TokenType::StringLiteral, Some(Value::String(name))) => name,
+            _ => token.get_source(),
+        };
+    }
+
+    fn negate(&mut self, value: Value) -> Value {
+        return value.negate();
+    }
+
+    fn is_null(&self, value: &Value) -> bool {
+        return matches!(value, Value::Null);
+    }
+
+    fn array(&mut self, items: Vec<Value>) -> Value {
+        return Value::Array(items);
+    }
+
+    fn object(&mut self, fields: HashMap<String, Value>) -> Value {
+        return Value::Object(fields);
+    }
+}
 
 pub struct SonParser<T>
 where
     T: Sized + Read,
 {
-    lexer: SonLexer<T>,
+    grammar: Grammar<T, OwnedBuilder>,
 }
 
 impl<T> SonParser<T>
@@ -19,31 +120,155 @@ where
     T: Sized + Read,
 {
     pub fn new(data: T) -> SonParser<T> {
+        return Self::with_options(data, ParseOptions::default());
+    }
+
+    pub fn with_options(data: T, options: ParseOptions) -> SonParser<T> {
         return Self {
-            lexer: SonLexer::new(data),
+            grammar: Grammar::new(data, options, OwnedBuilder),
         };
     }
 
     pub fn parse(&mut self) -> Result<Value, ParseError> {
+        return self.grammar.parse();
+    }
+
+    pub(crate) fn expect_end(&mut self) -> Result<(), ParseError> {
+        return self.grammar.expect_end();
+    }
+}
+
+pub(crate) struct Grammar<T, B>
+where
+    T: Sized + Read,
+    B: ValueBuilder,
+{
+    lexer: SonLexer<T>,
+    options: ParseOptions,
+    builder: B,
+    // The path of the value being parsed, only tracked when there are raw paths.
+    path: Path,
+}
+
+impl<T, B> Grammar<T, B>
+where
+    T: Sized + Read,
+    B: ValueBuilder,
+{
+    pub(crate) fn new(data: T, options: ParseOptions, builder: B) -> Grammar<T, B> {
+        return Self {
+            lexer: SonLexer::new(data),
+            options,
+            builder,
+            path: Path::root(),
+        };
+    }
+
+    pub(crate) fn parse(&mut self) -> Result<B::Value, ParseError> {
+        if self.options.raw_paths.contains(&self.path) {
+            self.lexer.start_recording("");
+            let result = self.parse_root();
+            let source = self.lexer.stop_recording();
+            return result.map(|_| self.builder.scalar(Value::Raw(RawSon::from_source(source))));
+        }
+        return self.parse_root();
+    }
+
+    pub(crate) fn into_builder(self) -> B {
+        return self.builder;
+    }
+
+    // Checks that only whitespace and comments follow the parsed document.
+    pub(crate) fn expect_end(&mut self) -> Result<(), ParseError> {
+        let token = self.lexer.next_token();
+        return match token.get_type() {
+            TokenType::EOF => Ok(()),
+            TokenType::Error => Err(ParseError::ErrorToken(ParseStep::Start, token)),
+            _ => Err(UnexpectedToken {
+                step: ParseStep::Start,
+                expected: &[TokenType::EOF],
+                found: token,
+                message: "Expected the end of the input".to_string(),
+            }),
+        };
+    }
+
+    fn parse_root(&mut self) -> Result<B::Value, ParseError> {
         let token = self.lexer.next_token();
         return match token.get_type() {
             // Expected tokens
             TokenType::LeftCurlyBrace => self.parse_object(),
             TokenType::LeftSquareBrace => self.parse_array(),
+            // Binary data, such as a serialized `Vec<u8>`, is a document on its own.
+            TokenType::BytesLiteral => Ok(self.builder.scalar(token.get_value().unwrap())),
 
             // Unexpected tokens
             TokenType::Error => Err(ParseError::ErrorToken(ParseStep::Start, token)),
             TokenType::EOF => Err(ParseError::UnexpectedEOF(ParseStep::Start)),
             _ => Err(UnexpectedToken {
                 step: ParseStep::Start,
-                expected: &[TokenType::LeftCurlyBrace, TokenType::LeftSquareBrace],
+                expected: &[
+                    TokenType::LeftCurlyBrace,
+                    TokenType::LeftSquareBrace,
+                    TokenType::BytesLiteral,
+                ],
                 found: token,
-                message: "SON files can only begin with either a { or [".to_string(),
+                message: "SON files can only begin with a {, a [ or a byte string".to_string(),
             }),
         };
     }
 
-    fn parse_value(&mut self) -> Result<Value, ParseError> {
+    // Parses an object field or array item, whose opening brace may have been consumed already.
+    fn parse_child<S>(&mut self, segment: S, opened: Option<TokenType>) -> Result<B::Value, ParseError>
+    where
+        S: FnOnce() -> PathSegment,
+    {
+        // Raw values inside a raw value are kept as part of its text.
+        if self.options.raw_paths.is_empty() || self.lexer.is_recording() {
+            return self.parse_opened(opened);
+        }
+
+        self.path.push(segment());
+        let result = if self.options.raw_paths.contains(&self.path) {
+            self.parse_raw(opened)
+        } else {
+            self.parse_opened(opened)
+        };
+        self.path.pop();
+        return result;
+    }
+
+    fn parse_opened(&mut self, opened: Option<TokenType>) -> Result<B::Value, ParseError> {
+        return match opened {
+            Some(TokenType::LeftCurlyBrace) => self.parse_object(),
+            Some(TokenType::LeftSquareBrace) => self.parse_array(),
+            _ => self.parse_value(),
+        };
+    }
+
+    // Parses a value to validate it, but returns its source text.
+    fn parse_raw(&mut self, opened: Option<TokenType>) -> Result<B::Value, ParseError> {
+        let prefix = match opened {
+            Some(TokenType::LeftCurlyBrace) => "{",
+            Some(TokenType::LeftSquareBrace) => "[",
+            _ => "",
+        };
+        self.lexer.start_recording(prefix);
+        let result = self.parse_opened(opened);
+        let source = self.lexer.stop_recording();
+        // `null` stays as is, so optional raw fields can be missing.
+        return result.map(|value| match self.builder.is_null(&value) {
+            true => value,
+            false => self.builder.scalar(Value::Raw(RawSon::from_source(source))),
+        });
+    }
+
+    fn parse_value(&mut self) -> Result<B::Value, ParseError> {
+        let token = self.lexer.next_token();
+        return self.parse_token(token);
+    }
+
+    fn parse_token(&mut self, token: Token) -> Result<B::Value, ParseError> {
         let expected_tokens: &'static [TokenType] = &[
             TokenType::LeftCurlyBrace,
             TokenType::LeftSquareBrace,
@@ -55,23 +280,53 @@ where
             TokenType::FloatLiteral,
             TokenType::StringLiteral,
             TokenType::CharLiteral,
+            TokenType::BytesLiteral,
+            TokenType::DateTimeLiteral,
+            TokenType::QuantityLiteral,
             TokenType::Identifier,
+            TokenType::QualifiedIdentifier,
         ];
 
-        let token = self.lexer.next_token();
         return match token.get_type() {
             // Expected tokens
             TokenType::LeftCurlyBrace => self.parse_object(),
             TokenType::LeftSquareBrace => self.parse_array(),
-            TokenType::Negative => Ok(self.parse_value()?.negate()),
+            TokenType::Negative => {
+                let operand = self.lexer.next_token();
+                match operand.get_type() {
+                    TokenType::Error | TokenType::EOF => self.parse_token(operand),
+                    token_type if NEGATABLE_TOKENS.contains(&token_type) => {
+                        let value = self.parse_token(operand)?;
+                        Ok(self.builder.negate(value))
+                    }
+                    _ => Err(UnexpectedToken {
+                        step: ParseStep::Value,
+                        expected: NEGATABLE_TOKENS,
+                        found: operand,
+                        message: "Only numbers can be negated".to_string(),
+                    }),
+                }
+            }
+            // Exponents, `inf` and `NaN` aren't exact decimals, so they are always read as floats.
+            TokenType::IntegerLiteral | TokenType::FloatLiteral if self.options.preserve_numbers => {
+                Ok(self.builder.scalar(match Number::new(&token.get_source()) {
+                    Some(number) => Value::Number(number),
+                    None => token.get_value().unwrap(),
+                }))
+            }
+            TokenType::StringLiteral => Ok(self.builder.string(&token, self.lexer.current_offset())),
             TokenType::True
             | TokenType::False
             | TokenType::Null
             | TokenType::IntegerLiteral
             | TokenType::FloatLiteral
-            | TokenType::StringLiteral
-            | TokenType::CharLiteral => Ok(token.get_value().unwrap()),
-            TokenType::Identifier => Ok(Value::Enum(token.get_source())),
+            | TokenType::CharLiteral
+            | TokenType::BytesLiteral
+            | TokenType::DateTimeLiteral
+            | TokenType::QuantityLiteral => Ok(self.builder.scalar(token.get_value().unwrap())),
+            TokenType::Identifier | TokenType::QualifiedIdentifier => {
+                Ok(self.builder.enum_variant(&token, self.lexer.current_offset()))
+            }
             TokenType::Comma => Ok(self.parse_value()?),
 
             // Unexpected tokens
@@ -86,20 +341,20 @@ where
         };
     }
 
-    fn parse_object(&mut self) -> Result<Value, ParseError> {
+    fn parse_object(&mut self) -> Result<B::Value, ParseError> {
         let expected_tokens: &'static [TokenType] = &[
             TokenType::LeftCurlyBrace,
             TokenType::RightCurlyBrace,
             TokenType::LeftSquareBrace,
             TokenType::Colon,
             TokenType::Identifier,
+            TokenType::StringLiteral,
         ];
 
-        let mut object_map: HashMap<String, Value> = HashMap::new();
-        let mut try_insert = |field_name: &mut String, value, token| {
-            if !field_name.is_empty() {
-                object_map.insert(field_name.clone(), value);
-                field_name.clear();
+        let mut object_map: HashMap<B::Key, B::Value> = HashMap::new();
+        let mut try_insert = |field_name: &mut Option<B::Key>, value, token| {
+            if let Some(name) = field_name.take() {
+                object_map.insert(name, value);
                 return Ok(());
             }
             return Err(UnexpectedToken {
@@ -110,15 +365,29 @@ where
             });
         };
 
-        let mut field_name = String::new();
+        let mut field_name = None;
         while let Some(token) = self.lexer.next() {
             match token.get_type() {
                 // Expected tokens
-                TokenType::Identifier => field_name = token.get_source(),
-                TokenType::Colon => try_insert(&mut field_name, self.parse_value()?, token)?,
-                TokenType::LeftCurlyBrace => try_insert(&mut field_name, self.parse_object()?, token)?,
-                TokenType::RightCurlyBrace => return Ok(Value::Object(object_map)),
-                TokenType::LeftSquareBrace => try_insert(&mut field_name, self.parse_array()?, token)?,
+                TokenType::Identifier | TokenType::StringLiteral if field_name.is_some() => {
+                    return Err(UnexpectedToken {
+                        step: ParseStep::Object,
+                        expected: &[TokenType::Colon],
+                        found: token,
+                        message: "Expected a colon after the field name".to_string(),
+                    });
+                }
+                TokenType::Identifier | TokenType::StringLiteral => {
+                    field_name = Some(self.builder.key(&token, self.lexer.current_offset()))
+                }
+                TokenType::Colon | TokenType::LeftCurlyBrace | TokenType::LeftSquareBrace => {
+                    let opened = Some(token.get_type()).filter(|t| *t != TokenType::Colon);
+                    let key =
+                        || PathSegment::Key(field_name.as_ref().map_or(String::new(), |k| k.as_ref().to_string()));
+                    let value = self.parse_child(key, opened)?;
+                    try_insert(&mut field_name, value, token)?
+                }
+                TokenType::RightCurlyBrace => return Ok(self.builder.object(object_map)),
                 TokenType::Comma => {}
 
                 // Unexpected tokens
@@ -137,17 +406,17 @@ where
         return Err(ParseError::UnexpectedEOF(ParseStep::Object));
     }
 
-    fn parse_array(&mut self) -> Result<Value, ParseError> {
+    fn parse_array(&mut self) -> Result<B::Value, ParseError> {
         let expected_tokens: &'static [TokenType] = &[TokenType::RightSquareBrace];
 
-        let mut value_array: Vec<Value> = Vec::new();
-        while let Ok(value) = self.parse_value() {
+        let mut value_array: Vec<B::Value> = Vec::new();
+        while let Ok(value) = self.parse_child(|| PathSegment::Index(value_array.len()), None) {
             value_array.push(value);
         }
 
         let current_token = self.lexer.current().unwrap();
         return match current_token.get_type() {
-            TokenType::RightSquareBrace => Ok(Value::Array(value_array)),
+            TokenType::RightSquareBrace => Ok(self.builder.array(value_array)),
             _ => Err(UnexpectedToken {
                 step: ParseStep::Array,
                 expected: expected_tokens,
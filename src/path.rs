@@ -0,0 +1,167 @@
+use crate::{Query, QueryError, Value};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl PathSegment {
+    fn is_identifier(key: &str) -> bool {
+        let mut chars = key.chars();
+        return match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_alphanumeric() || c == '_'),
+            _ => false,
+        };
+    }
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            PathSegment::Key(key) if PathSegment::is_identifier(key) => write!(f, ".{}", key),
+            PathSegment::Key(key) => {
+                write!(f, "[\"")?;
+                for c in key.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        '\r' => write!(f, "\\r")?,
+                        _ => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"]")
+            }
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        };
+    }
+}
+
+// A concrete location inside a Value, starting from the document root.
+// Displayed as `$` for the root, followed by `.key`, `["quoted key"]` and `[index]` segments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn root() -> Path {
+        return Path { segments: Vec::new() };
+    }
+
+    pub fn from_segments(segments: Vec<PathSegment>) -> Path {
+        return Path { segments };
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        return &self.segments;
+    }
+
+    pub fn is_root(&self) -> bool {
+        return self.segments.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.segments.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.segments.is_empty();
+    }
+
+    pub fn last(&self) -> Option<&PathSegment> {
+        return self.segments.last();
+    }
+
+    pub fn parent(&self) -> Option<Path> {
+        if self.is_root() {
+            return None;
+        }
+        return Some(Path::from_segments(self.segments[..self.segments.len() - 1].to_vec()));
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        return self.segments.pop();
+    }
+
+    pub fn key(&self, key: &str) -> Path {
+        let mut path = self.clone();
+        path.push(PathSegment::Key(key.to_string()));
+        return path;
+    }
+
+    pub fn index(&self, index: usize) -> Path {
+        let mut path = self.clone();
+        path.push(PathSegment::Index(index));
+        return path;
+    }
+
+    // Appends the segments of `other`, treating it as relative to this path.
+    pub fn join(&self, other: &Path) -> Path {
+        let mut path = self.clone();
+        path.segments.extend_from_slice(&other.segments);
+        return path;
+    }
+
+    pub fn starts_with(&self, other: &Path) -> bool {
+        return self.segments.starts_with(&other.segments);
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for segment in self.segments.iter() {
+            write!(f, "{}", segment)?;
+        }
+        return Ok(());
+    }
+}
+
+// Parses the `Display` representation of a path back, e.g. `$.servers[0]["max connections"]`.
+impl FromStr for Path {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Query::parse(s)?.to_path().ok_or_else(|| QueryError {
+            position: 0,
+            message: format!("'{}' is not a concrete path", s),
+        });
+    }
+}
+
+impl Value {
+    pub fn get_path(&self, path: &Path) -> Option<&Value> {
+        let mut current = self;
+        for segment in path.segments() {
+            current = match (current, segment) {
+                (Value::Object(o), PathSegment::Key(key)) => o.get(key)?,
+                (Value::Array(a), PathSegment::Index(index)) => a.get(*index)?,
+                _ => return None,
+            };
+        }
+        return Some(current);
+    }
+
+    pub fn get_path_mut(&mut self, path: &Path) -> Option<&mut Value> {
+        let mut current = self;
+        for segment in path.segments() {
+            current = match (current, segment) {
+                (Value::Object(o), PathSegment::Key(key)) => o.get_mut(key)?,
+                (Value::Array(a), PathSegment::Index(index)) => a.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        return Some(current);
+    }
+}
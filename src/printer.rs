@@ -1,66 +1,403 @@
-use crate::Value;
+use crate::{Value, bytes, lexer, units};
+use std::{
+    collections::HashMap,
+    io::{self, BufWriter, Write},
+};
+
+// Which commas the printer writes between array items and object entries. Commas are optional in SON, so all three
+// styles parse back to the same value.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Commas {
+    // Items are separated by whitespace only.
+    #[default]
+    None,
+    // A comma between items.
+    Separators,
+    // A comma after every item, including the last one.
+    Trailing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(&self) -> &'static str {
+        return match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        };
+    }
+}
+
+// When the pretty layout writes an array or object across several lines. Empty containers are always written as `[]`
+// and `{}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Wrapping {
+    // On one line when it fits within `max_width`, otherwise one item per line. Without a `max_width`, this is the same
+    // as `Always`.
+    #[default]
+    Fit,
+    // One item per line.
+    Always,
+    // On one line, however long it gets, unless it contains a container that always breaks.
+    Never,
+}
+
+// How values are laid out as text. `new()` is the pretty layout used by `Display` and `to_string_pretty`: arrays and
+// objects on one line when they fit within 80 characters and one item per line otherwise, four spaces of indentation
+// and no commas. `compact()` writes everything on a single line with commas and no
+// optional whitespace, as used by `to_string`. Raw values are written verbatim in both layouts, so they can still span
+// several lines.
+#[derive(Debug, Clone)]
+pub struct PrinterOptions {
+    pub pretty: bool,
+    pub indent: String,
+    pub commas: Commas,
+    pub space_after_colon: bool,
+    pub line_ending: LineEnding,
+    pub final_newline: bool,
+    pub sort_keys: bool,
+    pub qualify_enums: bool,
+    pub max_width: Option<usize>,
+    pub arrays: Wrapping,
+    pub objects: Wrapping,
+}
+
+impl Default for PrinterOptions {
+    fn default() -> Self {
+        return Self {
+            pretty: true,
+            indent: "    ".to_string(),
+            commas: Commas::None,
+            space_after_colon: true,
+            line_ending: LineEnding::Lf,
+            final_newline: true,
+            sort_keys: false,
+            qualify_enums: false,
+            max_width: Some(80),
+            arrays: Wrapping::Fit,
+            objects: Wrapping::Fit,
+        };
+    }
+}
+
+impl PrinterOptions {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn compact() -> Self {
+        return Self::default()
+            .pretty(false)
+            .commas(Commas::Separators)
+            .space_after_colon(false)
+            .final_newline(false);
+    }
+
+    // When disabled, the whole value is written on one line and `indent` and `line_ending` are ignored.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        return self;
+    }
+
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = indent.to_string();
+        return self;
+    }
+
+    pub fn commas(mut self, commas: Commas) -> Self {
+        self.commas = commas;
+        return self;
+    }
+
+    pub fn space_after_colon(mut self, space_after_colon: bool) -> Self {
+        self.space_after_colon = space_after_colon;
+        return self;
+    }
+
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        return self;
+    }
+
+    // Ends the output with a line ending, in both layouts.
+    pub fn final_newline(mut self, final_newline: bool) -> Self {
+        self.final_newline = final_newline;
+        return self;
+    }
+
+    // Writes object entries sorted by key instead of in the map's iteration order.
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        return self;
+    }
+
+    // When enabled, enum variants that carry a qualifier are written as `Status.Active` instead of `Active`. Variants
+    // named like a keyword, such as `Float.NaN`, always keep their qualifier.
+    pub fn qualify_enums(mut self, qualify_enums: bool) -> Self {
+        self.qualify_enums = qualify_enums;
+        return self;
+    }
+
+    // Lets the pretty layout keep arrays and objects on one line, as `[1 2 3]` or `{ x: 1 y: 2 }`, as long as the line
+    // stays within `max_width` characters including indentation. Counts characters, so a tab counts as one. Defaults to
+    // 80.
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        return self;
+    }
+
+    pub fn arrays(mut self, arrays: Wrapping) -> Self {
+        self.arrays = arrays;
+        return self;
+    }
+
+    pub fn objects(mut self, objects: Wrapping) -> Self {
+        self.objects = objects;
+        return self;
+    }
+}
+
+// Wraps `text` in `quote` characters, escaping `\`, the quote, `\n`, `\r`, `\t`, and other control characters as
+// `\u{..}`.
+pub(crate) fn quote(text: &str, quote: char) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push(quote);
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c == quote => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push(quote);
+    return quoted;
+}
 
 pub struct Printer {
-    indentation: String,
+    options: PrinterOptions,
 }
 
 impl Printer {
     pub fn new(indentation: String) -> Self {
-        return Self { indentation };
+        return Self::with_options(PrinterOptions::new().indent(&indentation));
+    }
+
+    pub fn with_options(options: PrinterOptions) -> Self {
+        return Self { options };
     }
 
     pub fn son_to_string(&self, son: &Value) -> String {
-        return self.son_to_string_inner(son, 0);
+        let mut output = Vec::new();
+        self.write_root(&mut output, son)
+            .expect("writing to a Vec doesn't fail");
+        return String::from_utf8(output).expect("the printer only writes UTF-8");
+    }
+
+    // Streams the printed value to `writer` through a single buffer, which is flushed before returning.
+    pub fn write_value<W: Write>(&self, writer: &mut W, son: &Value) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        self.write_root(&mut writer, son)?;
+        return writer.flush();
+    }
+
+    fn write_root<W: Write>(&self, writer: &mut W, son: &Value) -> io::Result<()> {
+        self.write_value_at(writer, son, 0, 0)?;
+        if self.options.final_newline {
+            writer.write_all(self.options.line_ending.as_str().as_bytes())?;
+        }
+        return Ok(());
+    }
+
+    // Writes a value that starts at `column` of its line, so the pretty layout can tell whether a container still fits on
+    // that line. Characters that have to follow the value on the same line, like a comma, are counted in `column`.
+    fn write_value_at<W: Write>(&self, writer: &mut W, son: &Value, indent: usize, column: usize) -> io::Result<()> {
+        if self.options.pretty
+            && matches!(son, Value::Array(_) | Value::Object(_))
+            && let Some(line) = self.inline(son, column)
+        {
+            return writer.write_all(&line);
+        }
+        return self.write_inner(writer, son, indent);
     }
 
-    fn son_to_string_inner(&self, son: &Value, indent: u32) -> String {
-        let mut string = String::new();
+    // The container written on one line, if its wrapping allows that from `column`.
+    fn inline(&self, son: &Value, column: usize) -> Option<Vec<u8>> {
+        let limit = match (self.wrapping(son), self.options.max_width) {
+            (Wrapping::Always, _) | (Wrapping::Fit, None) => return None,
+            (Wrapping::Never, _) => usize::MAX,
+            (Wrapping::Fit, Some(max_width)) => max_width.saturating_sub(column),
+        };
+        let mut line = Vec::new();
+        // A character takes at most four bytes, so anything longer than that can't fit.
+        if !self.write_flat(&mut line, son, limit.saturating_mul(4)) {
+            return None;
+        }
+        let width = String::from_utf8_lossy(&line).chars().count();
+        if width > limit {
+            return None;
+        }
+        return Some(line);
+    }
+
+    fn wrapping(&self, son: &Value) -> Wrapping {
+        return match son {
+            Value::Array(a) if !a.is_empty() => self.options.arrays,
+            Value::Object(o) if !o.is_empty() => self.options.objects,
+            _ => Wrapping::Never,
+        };
+    }
 
-        let print_indent = |string: &mut String, indent: u32| {
-            for _ in 0..indent {
-                string.push_str(&self.indentation);
+    // Writes `son` on a single line, giving up once it goes over `limit` bytes or meets something that has to span
+    // several lines.
+    fn write_flat(&self, line: &mut Vec<u8>, son: &Value, limit: usize) -> bool {
+        let items: Vec<(Option<&str>, &Value)> = match son {
+            Value::Raw(r) if r.as_str().contains('\n') => return false,
+            Value::Array(a) => a.iter().map(|item| (None, item)).collect(),
+            Value::Object(o) => self.entries(o),
+            _ => {
+                self.write_inner(line, son, 0).expect("writing to a Vec doesn't fail");
+                return line.len() <= limit;
             }
         };
+        if items.is_empty() {
+            line.extend_from_slice(if matches!(son, Value::Array(_)) { b"[]" } else { b"{}" });
+            return line.len() <= limit;
+        }
+        if self.wrapping(son) == Wrapping::Always {
+            return false;
+        }
 
-        match son {
-            Value::Null => string.push_str("null"),
-            Value::Bool(b) => string.push_str(&b.to_string()),
-            Value::Float(f) => string.push_str(&f.to_string()),
-            Value::Integer(i) => string.push_str(&i.to_string()),
-            Value::String(s) => {
-                string.push('\"');
-                string.push_str(&s);
-                string.push('\"');
+        let separator: &[u8] = if self.options.commas == Commas::None {
+            b" "
+        } else {
+            b", "
+        };
+        line.extend_from_slice(if matches!(son, Value::Array(_)) { b"[" } else { b"{ " });
+        for (i, (key, value)) in items.into_iter().enumerate() {
+            if i > 0 {
+                line.extend_from_slice(separator);
             }
-            Value::Char(c) => {
-                string.push('\'');
-                string.push_str(&c.to_string());
-                string.push('\'');
+            if let Some(key) = key {
+                self.write_key(line, key).expect("writing to a Vec doesn't fail");
             }
-            Value::Enum(s) => string.push_str(&s),
-            Value::Array(a) => {
-                string.push_str("[\n");
-                for value in a {
-                    print_indent(&mut string, indent + 1);
-                    string.push_str(&self.son_to_string_inner(&value, indent + 1));
-                }
-                print_indent(&mut string, indent);
-                string.push(']');
+            if line.len() > limit || !self.write_flat(line, value, limit) {
+                return false;
             }
-            Value::Object(o) => {
-                string.push_str("{\n");
-
-                for (key, value) in o.iter() {
-                    print_indent(&mut string, indent + 1);
-                    string.push_str(&key.to_string());
-                    string.push_str(": ");
-                    string.push_str(&self.son_to_string_inner(&value, indent + 1));
-                }
-                print_indent(&mut string, indent);
-                string.push('}');
+        }
+        line.extend_from_slice(if matches!(son, Value::Array(_)) { b"]" } else { b" }" });
+        return line.len() <= limit;
+    }
+
+    fn entries<'a>(&self, object: &'a HashMap<String, Value>) -> Vec<(Option<&'a str>, &'a Value)> {
+        let mut entries: Vec<(Option<&str>, &Value)> =
+            object.iter().map(|(key, value)| (Some(key.as_str()), value)).collect();
+        if self.options.sort_keys {
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+        return entries;
+    }
+
+    fn write_key<W: Write>(&self, writer: &mut W, key: &str) -> io::Result<()> {
+        if lexer::is_identifier(key) {
+            writer.write_all(key.as_bytes())?;
+        } else {
+            writer.write_all(quote(key, '"').as_bytes())?;
+        }
+        return writer.write_all(if self.options.space_after_colon { b": " } else { b":" });
+    }
+
+    fn write_inner<W: Write>(&self, writer: &mut W, son: &Value, indent: usize) -> io::Result<()> {
+        return match son {
+            Value::Null => writer.write_all(b"null"),
+            Value::Bool(b) => write!(writer, "{}", b),
+            // `Debug` writes the shortest text that reads back as the same float, and always includes a `.` or an
+            // exponent so it isn't read back as an integer: `1.0`, `0.1`, `1e-7`, `inf` and `NaN`.
+            Value::Float(f) => write!(writer, "{:?}", f),
+            Value::Integer(i) => write!(writer, "{}", i),
+            Value::BigInteger(b) => write!(writer, "{}", b),
+            Value::Number(n) => writer.write_all(n.as_str().as_bytes()),
+            Value::String(s) => writer.write_all(quote(s, '"').as_bytes()),
+            Value::Char(c) => writer.write_all(quote(&c.to_string(), '\'').as_bytes()),
+            // `NaN` alone would read back as a float, so keyword names keep their qualifier.
+            Value::Enum(e) if self.options.qualify_enums || lexer::is_keyword(e.name()) => write!(writer, "{}", e),
+            Value::Enum(e) => writer.write_all(e.name().as_bytes()),
+            Value::Bytes(b) => writer.write_all(bytes::encode_literal(b).as_bytes()),
+            Value::DateTime(d) => write!(writer, "{}", d),
+            Value::Duration(d) => writer.write_all(units::format_duration(d).as_bytes()),
+            Value::ByteSize(s) => write!(writer, "{}", s),
+            Value::Raw(r) => writer.write_all(r.as_str().as_bytes()),
+            Value::Array(a) => {
+                let items = a.iter().map(|item| (None, item)).collect();
+                self.write_items(writer, items, (b'[', b']'), indent)
             }
+            Value::Object(o) => self.write_items(writer, self.entries(o), (b'{', b'}'), indent),
         };
-        string.push('\n');
-        return string;
+    }
+
+    // Writes the items of an array, or the entries of an object when they come with a key.
+    fn write_items<W: Write>(
+        &self,
+        writer: &mut W,
+        items: Vec<(Option<&str>, &Value)>,
+        brackets: (u8, u8),
+        indent: usize,
+    ) -> io::Result<()> {
+        let options = &self.options;
+        writer.write_all(&[brackets.0])?;
+        if items.is_empty() {
+            return writer.write_all(&[brackets.1]);
+        }
+
+        let count = items.len();
+        for (i, (key, value)) in items.into_iter().enumerate() {
+            if options.pretty {
+                self.write_line_break(writer, indent + 1)?;
+            } else if i > 0 && options.commas == Commas::None {
+                writer.write_all(b" ")?;
+            }
+
+            let is_last = i + 1 == count;
+            let mut column = self.options.indent.chars().count() * (indent + 1);
+            if let Some(key) = key {
+                let mut written = Vec::new();
+                self.write_key(&mut written, key)?;
+                column += String::from_utf8_lossy(&written).chars().count();
+                writer.write_all(&written)?;
+            }
+            // Leave room for the comma that follows the value.
+            let comma = options.commas == Commas::Trailing || (options.commas == Commas::Separators && !is_last);
+            self.write_value_at(writer, value, indent + 1, column + comma as usize)?;
+
+            match options.commas {
+                Commas::Separators if !is_last => writer.write_all(b",")?,
+                Commas::Trailing => writer.write_all(b",")?,
+                _ => {}
+            }
+        }
+
+        if options.pretty {
+            self.write_line_break(writer, indent)?;
+        }
+        return writer.write_all(&[brackets.1]);
+    }
+
+    fn write_line_break<W: Write>(&self, writer: &mut W, indent: usize) -> io::Result<()> {
+        writer.write_all(self.options.line_ending.as_str().as_bytes())?;
+        for _ in 0..indent {
+            writer.write_all(self.options.indent.as_bytes())?;
+        }
+        return Ok(());
     }
 }
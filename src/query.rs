@@ -0,0 +1,714 @@
+use crate::{
+    bigint::BigInt,
+    error::QueryError,
+    path::{Path, PathSegment},
+    value::Value,
+    variant::EnumVariant,
+};
+use std::{cmp::Ordering, str::FromStr};
+
+// A compiled JSONPath-like selector.
+//
+// Supported syntax:
+//   $                    the document root
+//   .name  ["name"]      object member
+//   .*  [*]              every member of an object or element of an array
+//   ..name  ..*  ..[0]   recursive descent, applies the selector to a node and all its descendants
+//   [0]  [-1]            array element, negative indices count from the end
+//   [1:5:2]  [::-1]      array slice with optional start, end and step
+//   [0, 2, "name"]       union of several selectors
+//   [?@.port > 8000]     filter on the children of a node, `@` is the child and `$` the root.
+//                        Filters support `==`, `!=`, `<`, `<=`, `>`, `>=`, `&&`, `||`, `!`, parentheses
+//                        and existence tests such as `[?@.enabled]`. Bare identifiers are enum literals.
+//
+// Object members are visited in key order so results are deterministic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Match<'a> {
+    pub path: Path,
+    pub value: &'a Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    },
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Exists(FilterQuery),
+    Compare(Operand, Comparison, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FilterQuery {
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Value),
+    Query(FilterQuery),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Query, QueryError> {
+        return QueryParser::new(query).parse_query();
+    }
+
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<Match<'a>> {
+        let start = Match {
+            path: Path::root(),
+            value: root,
+        };
+        return select_segments(&self.segments, root, start);
+    }
+
+    pub fn select_values<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        return self.select(root).into_iter().map(|m| m.value).collect();
+    }
+
+    pub fn select_paths(&self, root: &Value) -> Vec<Path> {
+        return self.select(root).into_iter().map(|m| m.path).collect();
+    }
+
+    // Converts a query made only of member names and non-negative indices into a concrete path.
+    pub fn to_path(&self) -> Option<Path> {
+        let mut path = Path::root();
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Child(selectors) if selectors.len() == 1 => match &selectors[0] {
+                    Selector::Name(name) => path.push(PathSegment::Key(name.clone())),
+                    Selector::Index(index) if *index >= 0 => path.push(PathSegment::Index(*index as usize)),
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+        return Some(path);
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Query::parse(s);
+    }
+}
+
+pub fn select<'a>(value: &'a Value, query: &str) -> Result<Vec<Match<'a>>, QueryError> {
+    return Ok(Query::parse(query)?.select(value));
+}
+
+impl Value {
+    pub fn query(&self, query: &str) -> Result<Vec<Match<'_>>, QueryError> {
+        return select(self, query);
+    }
+}
+
+// Evaluation
+
+fn select_segments<'a>(segments: &[Segment], root: &'a Value, start: Match<'a>) -> Vec<Match<'a>> {
+    let mut nodes = vec![start];
+    for segment in segments {
+        let mut next = Vec::new();
+        for node in nodes.iter() {
+            match segment {
+                Segment::Child(selectors) => apply_selectors(selectors, root, node, &mut next),
+                Segment::Descendant(selectors) => {
+                    for descendant in descendants(node) {
+                        apply_selectors(selectors, root, &descendant, &mut next);
+                    }
+                }
+            }
+        }
+        nodes = next;
+    }
+    return nodes;
+}
+
+// The children of a node, objects ordered by key.
+fn children<'a>(node: &Match<'a>) -> Vec<Match<'a>> {
+    return match node.value {
+        Value::Object(o) => {
+            let mut keys: Vec<&String> = o.keys().collect();
+            keys.sort();
+            keys.into_iter()
+                .map(|key| Match {
+                    path: node.path.key(key),
+                    value: &o[key],
+                })
+                .collect()
+        }
+        Value::Array(a) => a
+            .iter()
+            .enumerate()
+            .map(|(index, value)| Match {
+                path: node.path.index(index),
+                value,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+}
+
+// The node itself followed by all of its descendants, in document order.
+fn descendants<'a>(node: &Match<'a>) -> Vec<Match<'a>> {
+    let mut result = vec![node.clone()];
+    for child in children(node) {
+        result.append(&mut descendants(&child));
+    }
+    return result;
+}
+
+fn normalize_index(index: i64, len: i64) -> i64 {
+    return if index >= 0 { index } else { len + index };
+}
+
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: i64, len: usize) -> Vec<usize> {
+    let len = len as i64;
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = normalize_index(start.unwrap_or(0), len).clamp(0, len);
+        let upper = normalize_index(end.unwrap_or(len), len).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i = match i.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    } else if step < 0 {
+        let upper = start.map_or(len - 1, |s| normalize_index(s, len)).clamp(-1, len - 1);
+        let lower = end.map_or(-1, |e| normalize_index(e, len)).clamp(-1, len - 1);
+        let mut i = upper;
+        while lower < i {
+            indices.push(i as usize);
+            i = match i.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+    return indices;
+}
+
+fn apply_selectors<'a>(selectors: &[Selector], root: &'a Value, node: &Match<'a>, result: &mut Vec<Match<'a>>) {
+    for selector in selectors {
+        match (selector, node.value) {
+            (Selector::Name(name), Value::Object(o)) => {
+                if let Some(value) = o.get(name) {
+                    result.push(Match {
+                        path: node.path.key(name),
+                        value,
+                    });
+                }
+            }
+            (Selector::Wildcard, _) => result.append(&mut children(node)),
+            (Selector::Index(index), Value::Array(a)) => {
+                let index = normalize_index(*index, a.len() as i64);
+                if index >= 0 && (index as usize) < a.len() {
+                    result.push(Match {
+                        path: node.path.index(index as usize),
+                        value: &a[index as usize],
+                    });
+                }
+            }
+            (Selector::Slice { start, end, step }, Value::Array(a)) => {
+                for index in slice_indices(*start, *end, *step, a.len()) {
+                    result.push(Match {
+                        path: node.path.index(index),
+                        value: &a[index],
+                    });
+                }
+            }
+            (Selector::Filter(filter), _) => {
+                for child in children(node) {
+                    if filter.test(root, child.value) {
+                        result.push(child);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FilterQuery {
+    fn select<'a>(&self, root: &'a Value, current: &'a Value) -> Vec<Match<'a>> {
+        let start = Match {
+            path: Path::root(),
+            value: if self.absolute { root } else { current },
+        };
+        return select_segments(&self.segments, root, start);
+    }
+
+    // Singular queries can only ever produce one node, which makes them usable in comparisons.
+    fn is_singular(&self) -> bool {
+        return self.segments.iter().all(|segment| match segment {
+            Segment::Child(selectors) => {
+                selectors.len() == 1 && matches!(selectors[0], Selector::Name(_) | Selector::Index(_))
+            }
+            Segment::Descendant(_) => false,
+        });
+    }
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, root: &'a Value, current: &'a Value) -> Option<&'a Value> {
+        return match self {
+            Operand::Literal(value) => Some(value),
+            Operand::Query(query) => query.select(root, current).first().map(|m| m.value),
+        };
+    }
+}
+
+impl Filter {
+    fn test(&self, root: &Value, current: &Value) -> bool {
+        return match self {
+            Filter::Or(left, right) => left.test(root, current) || right.test(root, current),
+            Filter::And(left, right) => left.test(root, current) && right.test(root, current),
+            Filter::Not(filter) => !filter.test(root, current),
+            Filter::Exists(query) => !query.select(root, current).is_empty(),
+            Filter::Compare(left, comparison, right) => {
+                let left = left.resolve(root, current);
+                let right = right.resolve(root, current);
+                match comparison {
+                    Comparison::Equal => values_equal(left, right),
+                    Comparison::NotEqual => !values_equal(left, right),
+                    Comparison::Less => values_order(left, right) == Some(Ordering::Less),
+                    Comparison::LessEqual => {
+                        matches!(values_order(left, right), Some(Ordering::Less | Ordering::Equal))
+                    }
+                    Comparison::Greater => values_order(left, right) == Some(Ordering::Greater),
+                    Comparison::GreaterEqual => {
+                        matches!(values_order(left, right), Some(Ordering::Greater | Ordering::Equal))
+                    }
+                }
+            }
+        };
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    return match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::BigInteger(b) => Some(b.to_f64()),
+        Value::Float(f) => Some(*f),
+        Value::Number(n) => Some(n.to_f64()),
+        _ => None,
+    };
+}
+
+fn values_equal(left: Option<&Value>, right: Option<&Value>) -> bool {
+    return match (left, right) {
+        (None, None) => true,
+        (Some(l), Some(r)) if l == r => true,
+        (Some(l), Some(r)) => values_order(Some(l), Some(r)) == Some(Ordering::Equal),
+        _ => false,
+    };
+}
+
+fn values_order(left: Option<&Value>, right: Option<&Value>) -> Option<Ordering> {
+    return match (left?, right?) {
+        (Value::Integer(l), Value::Integer(r)) => Some(l.cmp(r)),
+        (Value::BigInteger(l), Value::BigInteger(r)) => Some(l.cmp(r)),
+        (Value::Integer(l), Value::BigInteger(r)) => Some(BigInt::from(*l).cmp(r)),
+        (Value::BigInteger(l), Value::Integer(r)) => Some(l.cmp(&BigInt::from(*r))),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (Value::Char(l), Value::Char(r)) => Some(l.cmp(r)),
+        (Value::Bytes(l), Value::Bytes(r)) => Some(l.cmp(r)),
+        (l, r) if l == r => Some(Ordering::Equal),
+        (l, r) => as_f64(l)?.partial_cmp(&as_f64(r)?),
+    };
+}
+
+// Parsing
+
+struct QueryParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl QueryParser {
+    fn new(query: &str) -> QueryParser {
+        return QueryParser {
+            chars: query.chars().collect(),
+            position: 0,
+        };
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, QueryError> {
+        return Err(QueryError {
+            position: self.position,
+            message: message.to_string(),
+        });
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.position).cloned();
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        return self.chars.get(self.position + 1).cloned();
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.position += 1;
+        }
+        return c;
+    }
+
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            return true;
+        }
+        return false;
+    }
+
+    fn match_str(&mut self, expected: &str) -> bool {
+        let matches = expected
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.position + i) == Some(&c));
+        if matches {
+            self.position += expected.chars().count();
+        }
+        return matches;
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), QueryError> {
+        if !self.match_char(expected) {
+            return self.error(&format!("Expected '{}'", expected));
+        }
+        return Ok(());
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn parse_query(&mut self) -> Result<Query, QueryError> {
+        self.skip_whitespace();
+        self.expect('$')?;
+        let segments = self.parse_segments()?;
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return self.error("Unexpected character");
+        }
+        return Ok(Query { segments });
+    }
+
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, QueryError> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') if self.peek_next() == Some('.') => {
+                    self.position += 2;
+                    let selectors = match self.peek() {
+                        Some('[') => self.parse_bracket()?,
+                        _ => vec![self.parse_dot_selector()?],
+                    };
+                    segments.push(Segment::Descendant(selectors));
+                }
+                Some('.') => {
+                    self.advance();
+                    segments.push(Segment::Child(vec![self.parse_dot_selector()?]));
+                }
+                Some('[') => segments.push(Segment::Child(self.parse_bracket()?)),
+                _ => return Ok(segments),
+            }
+        }
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Selector, QueryError> {
+        if self.match_char('*') {
+            return Ok(Selector::Wildcard);
+        }
+        let name = self.parse_identifier();
+        if name.is_empty() {
+            return self.error("Expected a member name or '*'");
+        }
+        return Ok(Selector::Name(name));
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let mut identifier = String::new();
+        if self.peek().is_some_and(|c| c.is_alphabetic() || c == '_') {
+            while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                identifier.push(c);
+                self.advance();
+            }
+        }
+        return identifier;
+    }
+
+    fn parse_bracket(&mut self) -> Result<Vec<Selector>, QueryError> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.parse_bracket_selector()?);
+            self.skip_whitespace();
+            if self.match_char(']') {
+                return Ok(selectors);
+            }
+            if !self.match_char(',') {
+                return self.error("Expected ',' or ']'");
+            }
+        }
+    }
+
+    fn parse_bracket_selector(&mut self) -> Result<Selector, QueryError> {
+        return match self.peek() {
+            Some('*') => {
+                self.advance();
+                Ok(Selector::Wildcard)
+            }
+            Some('"') | Some('\'') => Ok(Selector::Name(self.parse_string()?)),
+            Some('?') => {
+                self.advance();
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.parse_or()?))
+            }
+            Some('-') | Some('0'..='9') | Some(':') => self.parse_index_or_slice(),
+            _ => self.error("Expected a selector"),
+        };
+    }
+
+    fn parse_index_or_slice(&mut self) -> Result<Selector, QueryError> {
+        let start = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        if !self.match_char(':') {
+            return match start {
+                Some(index) => Ok(Selector::Index(index)),
+                None => self.error("Expected an index"),
+            };
+        }
+
+        self.skip_whitespace();
+        let end = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        let mut step = 1;
+        if self.match_char(':') {
+            self.skip_whitespace();
+            step = self.parse_optional_integer()?.unwrap_or(1);
+        }
+        return Ok(Selector::Slice { start, end, step });
+    }
+
+    fn parse_optional_integer(&mut self) -> Result<Option<i64>, QueryError> {
+        let start = self.position;
+        self.match_char('-');
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+        }
+        if self.position == start {
+            return Ok(None);
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        return match text.parse::<i64>() {
+            Ok(integer) => Ok(Some(integer)),
+            Err(_) => self.error("Invalid integer"),
+        };
+    }
+
+    fn parse_string(&mut self) -> Result<String, QueryError> {
+        let quote = self.advance().unwrap();
+        let mut string = String::new();
+        loop {
+            match self.advance() {
+                None => return self.error("Unterminated string"),
+                Some(c) if c == quote => return Ok(string),
+                Some('\\') => match self.advance() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some(c @ ('"' | '\'' | '\\')) => string.push(c),
+                    _ => return self.error("Invalid escape sequence"),
+                },
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, QueryError> {
+        let mut filter = self.parse_and()?;
+        self.skip_whitespace();
+        while self.match_str("||") {
+            self.skip_whitespace();
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+            self.skip_whitespace();
+        }
+        return Ok(filter);
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, QueryError> {
+        let mut filter = self.parse_unary()?;
+        self.skip_whitespace();
+        while self.match_str("&&") {
+            self.skip_whitespace();
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_unary()?));
+            self.skip_whitespace();
+        }
+        return Ok(filter);
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, QueryError> {
+        if self.peek() == Some('!') && self.peek_next() != Some('=') {
+            self.advance();
+            self.skip_whitespace();
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.match_char('(') {
+            self.skip_whitespace();
+            let filter = self.parse_or()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(filter);
+        }
+        return self.parse_comparison();
+    }
+
+    fn parse_comparison(&mut self) -> Result<Filter, QueryError> {
+        let left = self.parse_operand()?;
+        self.skip_whitespace();
+
+        let comparison = if self.match_str("==") {
+            Comparison::Equal
+        } else if self.match_str("!=") {
+            Comparison::NotEqual
+        } else if self.match_str("<=") {
+            Comparison::LessEqual
+        } else if self.match_str(">=") {
+            Comparison::GreaterEqual
+        } else if self.match_char('<') {
+            Comparison::Less
+        } else if self.match_char('>') {
+            Comparison::Greater
+        } else {
+            return match left {
+                Operand::Query(query) => Ok(Filter::Exists(query)),
+                Operand::Literal(_) => self.error("Expected a comparison operator"),
+            };
+        };
+
+        self.skip_whitespace();
+        let right = self.parse_operand()?;
+        for operand in [&left, &right] {
+            if let Operand::Query(query) = operand
+                && !query.is_singular()
+            {
+                return self.error("Only queries selecting a single value can be compared");
+            }
+        }
+        return Ok(Filter::Compare(left, comparison, right));
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, QueryError> {
+        return match self.peek() {
+            Some('@') | Some('$') => {
+                let absolute = self.advance() == Some('$');
+                let segments = self.parse_segments()?;
+                Ok(Operand::Query(FilterQuery { absolute, segments }))
+            }
+            Some('"') | Some('\'') => Ok(Operand::Literal(Value::String(self.parse_string()?))),
+            Some('-') | Some('0'..='9') => Ok(Operand::Literal(self.parse_number()?)),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let identifier = self.parse_identifier();
+                Ok(Operand::Literal(match identifier.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    "null" => Value::Null,
+                    _ => Value::Enum(EnumVariant::from(identifier)),
+                }))
+            }
+            _ => self.error("Expected a query or a literal"),
+        };
+    }
+
+    fn parse_number(&mut self) -> Result<Value, QueryError> {
+        let start = self.position;
+        let mut is_float = false;
+        self.match_char('-');
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='9' => {}
+                '.' | 'e' | 'E' => is_float = true,
+                '+' | '-' if matches!(self.chars[self.position - 1], 'e' | 'E') => {}
+                _ => break,
+            }
+            self.advance();
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        let value = match is_float {
+            true => text.parse::<f64>().ok().map(Value::Float),
+            false => text.parse::<i128>().ok().map(Value::Integer),
+        };
+        return match value {
+            Some(value) => Ok(value),
+            None => self.error("Invalid number"),
+        };
+    }
+}
+
+impl From<&PathSegment> for Selector {
+    fn from(segment: &PathSegment) -> Self {
+        return match segment {
+            PathSegment::Key(key) => Selector::Name(key.clone()),
+            PathSegment::Index(index) => Selector::Index(*index as i64),
+        };
+    }
+}
+
+impl From<&Path> for Query {
+    fn from(path: &Path) -> Self {
+        return Query {
+            segments: path
+                .segments()
+                .iter()
+                .map(|segment| Segment::Child(vec![Selector::from(segment)]))
+                .collect(),
+        };
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    DeserializationError, Deserialize, Error, FromSon, ParseOptions, Path, Serialize, ToSon, Value, parser::SonParser,
+};
+use std::fmt::{Display, Formatter};
+
+// The unparsed source text of a single value, for fields that are processed later or by someone else.
+//
+// When a struct with a `RawSon` field is deserialized from text, the parser copies the field's source verbatim,
+// including its formatting and comments, and the serializer writes it back unchanged. `parse` deserializes it on
+// demand. A `RawSon` built from a `Value` that wasn't parsed from text, or nested inside an array or a map, holds the
+// printed value instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawSon {
+    source: String,
+}
+
+impl RawSon {
+    // Checks that `source` holds exactly one value.
+    pub fn new(source: &str) -> Result<RawSon, Error> {
+        parse_single::<Value>(source, ParseOptions::default())?;
+        return Ok(RawSon::from_source(source.to_string()));
+    }
+
+    // Only for text that the parser already validated.
+    pub(crate) fn from_source(source: String) -> RawSon {
+        return RawSon { source };
+    }
+
+    pub fn from_value(value: &Value) -> RawSon {
+        if let Value::Raw(raw) = value {
+            return raw.clone();
+        }
+        return RawSon::from_source(value.to_string().trim_end().to_string());
+    }
+
+    pub(crate) fn heap_size(&self) -> usize {
+        return self.source.capacity();
+    }
+
+    pub fn as_str(&self) -> &str {
+        return &self.source;
+    }
+
+    pub fn into_string(self) -> String {
+        return self.source;
+    }
+
+    pub fn parse<T>(&self) -> Result<T, Error>
+    where
+        T: Deserialize,
+    {
+        return self.parse_with(ParseOptions::default());
+    }
+
+    pub fn parse_with<T>(&self, options: ParseOptions) -> Result<T, Error>
+    where
+        T: Deserialize,
+    {
+        return parse_single(&self.source, options);
+    }
+}
+
+// Documents must start with `{` or `[`, so the value is parsed as the only item of an array. Anything after that array
+// means the source closed it early, as in `1] [2`.
+fn parse_single<T>(source: &str, mut options: ParseOptions) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    let item = Path::root().index(0);
+    options.raw_paths = options.raw_paths.iter().map(|path| item.join(path)).collect();
+    options
+        .raw_paths
+        .extend(T::raw_paths().iter().map(|path| item.join(path)));
+
+    // The newlines end any trailing line comment before the closing bracket.
+    let wrapped = format!("[\n{}\n]", source);
+    let mut parser = SonParser::with_options(wrapped.as_bytes(), options);
+    let Value::Array(mut items) = parser.parse()? else {
+        unreachable!("the wrapped source is an array");
+    };
+    parser.expect_end()?;
+    if items.len() != 1 {
+        return Err(Error::DeserializationError(DeserializationError::InvalidValue {
+            message: format!("raw SON must hold exactly one value, found {}", items.len()),
+        }));
+    }
+    return Ok(T::from_son(items.remove(0))?);
+}
+
+impl Display for RawSon {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.source);
+    }
+}
+
+impl Serialize for RawSon {}
+impl ToSon for RawSon {
+    fn to_son(&self) -> Value {
+        return Value::Raw(self.clone());
+    }
+}
+
+impl Deserialize for RawSon {}
+impl FromSon for RawSon {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::Raw(raw) => Ok(raw),
+            value => Ok(RawSon::from_value(&value)),
+        };
+    }
+
+    fn raw_paths() -> Vec<Path> {
+        return vec![Path::root()];
+    }
+}
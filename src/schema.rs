@@ -0,0 +1,408 @@
+use crate::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+// The shape shared by a set of sample documents, used to generate Rust types for them.
+//
+// Samples are inferred one by one and unified: fields missing from some objects become optional, `null` makes a type
+// nullable, integers widen to floats, identifiers collect into the variants of an enum, and the items of an array are
+// unified into one element type. Enum variants keep the type name they were qualified with, and variants qualified with
+// different type names don't unify. Values whose types disagree in any other way become `Any`, which is generated as a
+// plain `Value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    // Nothing has been seen yet, such as the items of arrays that were always empty.
+    Unknown,
+    Null,
+    Bool,
+    // The smallest and largest samples, to pick the integer type.
+    Integer {
+        min: i128,
+        max: i128,
+    },
+    Float,
+    String,
+    Char,
+    Bytes,
+    DateTime,
+    Duration,
+    ByteSize,
+    // The type name is the last segment of the variants' qualifier, if any of them had one.
+    Enum {
+        type_name: Option<String>,
+        variants: BTreeSet<String>,
+    },
+    Array(Box<Schema>),
+    Object(BTreeMap<String, Field>),
+    Nullable(Box<Schema>),
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub schema: Schema,
+    // True when some of the sampled objects don't have the field.
+    pub optional: bool,
+}
+
+impl Schema {
+    pub fn infer(value: &Value) -> Schema {
+        return match value {
+            Value::Null => Schema::Null,
+            Value::Bool(_) => Schema::Bool,
+            Value::Integer(i) => Schema::Integer { min: *i, max: *i },
+            Value::Float(_) => Schema::Float,
+            Value::Number(n) => match n.as_str().parse::<i128>() {
+                Ok(i) => Schema::Integer { min: i, max: i },
+                Err(_) if !n.has_fraction() => Schema::Any,
+                Err(_) => Schema::Float,
+            },
+            // Only integers outside of the `i128` range are stored as `BigInteger`.
+            Value::BigInteger(_) | Value::Raw(_) => Schema::Any,
+            Value::String(_) => Schema::String,
+            Value::Char(_) => Schema::Char,
+            Value::Enum(variant) => Schema::Enum {
+                type_name: variant.type_name().map(str::to_string),
+                variants: BTreeSet::from([variant.name().to_string()]),
+            },
+            Value::Bytes(_) => Schema::Bytes,
+            Value::DateTime(_) => Schema::DateTime,
+            Value::Duration(_) => Schema::Duration,
+            Value::ByteSize(_) => Schema::ByteSize,
+            Value::Array(items) => Schema::Array(Box::new(Schema::infer_all(items))),
+            Value::Object(object) => Schema::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| {
+                        let field = Field {
+                            schema: Schema::infer(value),
+                            optional: false,
+                        };
+                        return (key.clone(), field);
+                    })
+                    .collect(),
+            ),
+        };
+    }
+
+    // Infers the schema that fits all the samples.
+    pub fn infer_all<'a, I>(samples: I) -> Schema
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        return samples
+            .into_iter()
+            .fold(Schema::Unknown, |schema, sample| schema.unify(Schema::infer(sample)));
+    }
+
+    // The most specific schema that fits the values of both schemas.
+    pub fn unify(self, other: Schema) -> Schema {
+        return match (self, other) {
+            (Schema::Unknown, other) | (other, Schema::Unknown) => other,
+            (Schema::Any, _) | (_, Schema::Any) => Schema::Any,
+            (Schema::Null, Schema::Null) => Schema::Null,
+            (Schema::Null, other) | (other, Schema::Null) => other.nullable(),
+            (Schema::Nullable(a), b) | (b, Schema::Nullable(a)) => a.unify(b.non_null()).nullable(),
+            (Schema::Integer { min: a_min, max: a_max }, Schema::Integer { min: b_min, max: b_max }) => {
+                Schema::Integer {
+                    min: a_min.min(b_min),
+                    max: a_max.max(b_max),
+                }
+            }
+            (Schema::Integer { .. } | Schema::Float, Schema::Integer { .. } | Schema::Float) => Schema::Float,
+            (Schema::Enum { type_name: Some(a), .. }, Schema::Enum { type_name: Some(b), .. }) if a != b => Schema::Any,
+            (
+                Schema::Enum {
+                    type_name: a_name,
+                    variants: mut a,
+                },
+                Schema::Enum {
+                    type_name: b_name,
+                    variants: b,
+                },
+            ) => {
+                a.extend(b);
+                Schema::Enum {
+                    type_name: a_name.or(b_name),
+                    variants: a,
+                }
+            }
+            (Schema::Array(a), Schema::Array(b)) => Schema::Array(Box::new(a.unify(*b))),
+            (Schema::Object(mut a), Schema::Object(mut b)) => {
+                for (key, field) in a.iter_mut() {
+                    *field = match b.remove(key) {
+                        Some(other) => Field {
+                            schema: field.schema.clone().unify(other.schema),
+                            optional: field.optional || other.optional,
+                        },
+                        None => Field {
+                            schema: field.schema.clone(),
+                            optional: true,
+                        },
+                    };
+                }
+                for (key, field) in b {
+                    a.insert(
+                        key,
+                        Field {
+                            schema: field.schema,
+                            optional: true,
+                        },
+                    );
+                }
+                Schema::Object(a)
+            }
+            (a, b) if a == b => a,
+            _ => Schema::Any,
+        };
+    }
+
+    fn nullable(self) -> Schema {
+        return match self {
+            Schema::Null | Schema::Nullable(_) | Schema::Any => self,
+            _ => Schema::Nullable(Box::new(self)),
+        };
+    }
+
+    fn non_null(self) -> Schema {
+        return match self {
+            Schema::Null => Schema::Unknown,
+            Schema::Nullable(schema) => *schema,
+            _ => self,
+        };
+    }
+
+    // Generates Rust definitions using the `son` derives, with `name` as the name of the root type.
+    //
+    // Objects become structs named after their field, and array items are named after the singular of the field.
+    // Objects with keys that can't be field names, or that are always empty, become `HashMap<String, Value>`. Enums
+    // are named after their type name so that the derived `Deserialize` accepts the qualified variants, and enums
+    // sharing a type name become one enum with all their variants.
+    pub fn to_rust(&self, name: &str) -> String {
+        let root_name = pascal_case(name);
+        let mut enums = HashMap::new();
+        self.collect_enums(&mut enums);
+        enums.remove(&root_name);
+        let mut generator = RustGenerator {
+            names: enums.keys().cloned().collect(),
+            definitions: Vec::new(),
+            enums: enums
+                .into_iter()
+                .map(|(name, variants)| (name, Some(variants)))
+                .collect(),
+        };
+        match self {
+            Schema::Object(fields) if is_struct(fields) => generator.define_struct(&root_name, fields),
+            _ => {
+                generator.names.insert(root_name.clone());
+                let index = generator.reserve();
+                let root_type = generator.rust_type(self, &root_name);
+                generator.definitions[index] = format!("pub type {} = {};\n", root_name, root_type);
+            }
+        }
+
+        let mut output =
+            "use son::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};\n".to_string();
+        for definition in generator.definitions {
+            output.push('\n');
+            output.push_str(&definition);
+        }
+        return output;
+    }
+
+    // The variants of every enum with a type name that can be used as is, by type name.
+    fn collect_enums(&self, enums: &mut HashMap<String, BTreeSet<String>>) {
+        match self {
+            Schema::Enum {
+                type_name: Some(type_name),
+                variants,
+            } if identifier(type_name).as_deref() == Some(type_name.as_str())
+                && variants.iter().all(|variant| identifier(variant).is_some()) =>
+            {
+                enums
+                    .entry(type_name.clone())
+                    .or_default()
+                    .extend(variants.iter().cloned());
+            }
+            Schema::Array(schema) | Schema::Nullable(schema) => schema.collect_enums(enums),
+            Schema::Object(fields) => {
+                for field in fields.values() {
+                    field.schema.collect_enums(enums);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+const KEYWORDS: [&str; 39] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+    "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+// Keywords that can't be written as raw identifiers either.
+const RESERVED: [&str; 5] = ["crate", "self", "Self", "super", "_"];
+
+// The identifier for a field or variant name, or `None` if it can't be one.
+fn identifier(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid || RESERVED.contains(&name) {
+        return None;
+    }
+    if KEYWORDS.contains(&name) {
+        return Some(format!("r#{}", name));
+    }
+    return Some(name.to_string());
+}
+
+fn is_struct(fields: &BTreeMap<String, Field>) -> bool {
+    return !fields.is_empty() && fields.keys().all(|key| identifier(key).is_some());
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut capitalize = true;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            capitalize = true;
+            continue;
+        }
+        if capitalize {
+            result.push(c.to_ascii_uppercase());
+        } else {
+            result.push(c);
+        }
+        capitalize = false;
+    }
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        result.insert(0, 'T');
+    }
+    return result;
+}
+
+// A naive singular for naming array items: `replicas` -> `Replica`, `entries` -> `Entry`.
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+    if let Some(stem) = name.strip_suffix('s')
+        && !stem.is_empty()
+        && !stem.ends_with('s')
+    {
+        return stem.to_string();
+    }
+    return format!("{}Item", name);
+}
+
+fn is_snake_case(name: &str) -> bool {
+    return !name.chars().any(|c| c.is_ascii_uppercase());
+}
+
+fn is_camel_case(name: &str) -> bool {
+    return name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_');
+}
+
+struct RustGenerator {
+    names: HashSet<String>,
+    // Definitions in the order they were first referenced, parents before their fields' types.
+    definitions: Vec<String>,
+    // The variants of the enums named after their type name, taken once the enum is defined.
+    enums: HashMap<String, Option<BTreeSet<String>>>,
+}
+
+impl RustGenerator {
+    fn reserve(&mut self) -> usize {
+        self.definitions.push(String::new());
+        return self.definitions.len() - 1;
+    }
+
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut suffix = 2;
+        while self.names.contains(&unique) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+        self.names.insert(unique.clone());
+        return unique;
+    }
+
+    fn rust_type(&mut self, schema: &Schema, hint: &str) -> String {
+        return match schema {
+            Schema::Unknown | Schema::Any => "Value".to_string(),
+            Schema::Null => "Option<Value>".to_string(),
+            Schema::Bool => "bool".to_string(),
+            Schema::Integer { min, max } if *min >= i64::MIN as i128 && *max <= i64::MAX as i128 => "i64".to_string(),
+            Schema::Integer { .. } => "i128".to_string(),
+            Schema::Float => "f64".to_string(),
+            Schema::String => "String".to_string(),
+            Schema::Char => "char".to_string(),
+            Schema::Bytes => "son::Bytes".to_string(),
+            Schema::DateTime => "son::DateTime".to_string(),
+            Schema::Duration => "std::time::Duration".to_string(),
+            Schema::ByteSize => "son::ByteSize".to_string(),
+            Schema::Enum {
+                type_name: Some(type_name),
+                ..
+            } if self.enums.contains_key(type_name) => {
+                if let Some(variants) = self.enums.get_mut(type_name).and_then(Option::take) {
+                    self.define_enum(type_name, &variants);
+                }
+                type_name.clone()
+            }
+            Schema::Enum {
+                type_name: None,
+                variants,
+            } if variants.iter().all(|v| identifier(v).is_some()) => {
+                let name = self.unique_name(&pascal_case(hint));
+                self.define_enum(&name, variants);
+                name
+            }
+            Schema::Enum { .. } => "Value".to_string(),
+            Schema::Array(item) => format!("Vec<{}>", self.rust_type(item, &singular(hint))),
+            Schema::Object(fields) if is_struct(fields) => {
+                let name = self.unique_name(&pascal_case(hint));
+                self.define_struct(&name, fields);
+                name
+            }
+            Schema::Object(_) => "std::collections::HashMap<String, Value>".to_string(),
+            Schema::Nullable(schema) => format!("Option<{}>", self.rust_type(schema, hint)),
+        };
+    }
+
+    fn define_struct(&mut self, name: &str, fields: &BTreeMap<String, Field>) {
+        self.names.insert(name.to_string());
+        let index = self.reserve();
+        let mut definition = String::new();
+        if !fields.keys().all(|key| is_snake_case(key)) {
+            definition.push_str("#[allow(non_snake_case)]\n");
+        }
+        definition.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        definition.push_str(&format!("pub struct {} {{\n", name));
+        for (key, field) in fields {
+            let mut rust_type = self.rust_type(&field.schema, key);
+            if field.optional && !rust_type.starts_with("Option<") {
+                rust_type = format!("Option<{}>", rust_type);
+            }
+            definition.push_str(&format!("    pub {}: {},\n", identifier(key).unwrap(), rust_type));
+        }
+        definition.push_str("}\n");
+        self.definitions[index] = definition;
+    }
+
+    fn define_enum(&mut self, name: &str, variants: &BTreeSet<String>) {
+        let mut definition = String::new();
+        if !is_camel_case(name) || !variants.iter().all(|variant| is_camel_case(variant)) {
+            definition.push_str("#[allow(non_camel_case_types)]\n");
+        }
+        definition.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n");
+        definition.push_str(&format!("pub enum {} {{\n", name));
+        for variant in variants {
+            definition.push_str(&format!("    {},\n", identifier(variant).unwrap()));
+        }
+        definition.push_str("}\n");
+        self.definitions.push(definition);
+    }
+}
@@ -1,9 +1,17 @@
-use crate::Value;
+use crate::{BigInt, Value};
 use std::collections::HashMap;
 
 pub trait Serialize: ToSon {}
 pub trait ToSon {
     fn to_son(&self) -> Value;
+
+    // Used by `Vec<Self>`, so element types can pick another representation for a list of themselves.
+    fn vec_to_son(items: &[Self]) -> Value
+    where
+        Self: Sized,
+    {
+        return Value::Array(items.iter().map(|v| v.to_son()).collect());
+    }
 }
 
 // String types
@@ -89,6 +97,11 @@ impl ToSon for u8 {
     fn to_son(&self) -> Value {
         return Value::Integer(*self as i128);
     }
+
+    // Binary data is written as a byte string rather than an array of integers.
+    fn vec_to_son(items: &[Self]) -> Value {
+        return Value::Bytes(items.to_vec());
+    }
 }
 
 impl Serialize for u16 {}
@@ -112,7 +125,13 @@ impl ToSon for u64 {
     }
 }
 
-// NOTE: You cannot safely serialize u128 because they are internally stored as i128
+// Values above `i128::MAX` are stored as a `BigInteger`.
+impl Serialize for u128 {}
+impl ToSon for u128 {
+    fn to_son(&self) -> Value {
+        return Value::from(BigInt::from(*self));
+    }
+}
 
 impl Serialize for usize {}
 impl ToSon for usize {
@@ -126,6 +145,12 @@ impl ToSon for usize {
 impl Serialize for f32 {}
 impl ToSon for f32 {
     fn to_son(&self) -> Value {
+        // Widening keeps the binary value, so `0.1f32` would print as `0.10000000149011612`. The shortest decimal
+        // text of the `f32` gives a float that prints the way it was written, when it narrows back to the same `f32`.
+        let shortest = self.to_string().parse::<f64>().unwrap_or(*self as f64);
+        if shortest as f32 == *self {
+            return Value::Float(shortest);
+        }
         return Value::Float(*self as f64);
     }
 }
@@ -137,6 +162,21 @@ impl ToSon for f64 {
     }
 }
 
+// Option
+
+impl<T> Serialize for Option<T> where T: Serialize {}
+impl<T> ToSon for Option<T>
+where
+    T: ToSon,
+{
+    fn to_son(&self) -> Value {
+        return match self {
+            Some(value) => value.to_son(),
+            None => Value::Null,
+        };
+    }
+}
+
 // Vector
 
 impl<T> Serialize for Vec<T> where T: Serialize {}
@@ -145,7 +185,7 @@ where
     T: Serialize,
 {
     fn to_son(&self) -> Value {
-        return Value::Array(self.iter().map(|v| v.to_son()).collect());
+        return T::vec_to_son(self);
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::Value;
+use crate::{BigInt, DateTime, Value, bytes, units};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,7 +21,11 @@ pub enum TokenType {
     FloatLiteral,
     StringLiteral,
     CharLiteral,
+    BytesLiteral,
+    DateTimeLiteral,
+    QuantityLiteral,
     Identifier,
+    QualifiedIdentifier,
 
     Error,
     EOF,
@@ -64,28 +68,58 @@ impl Token {
             TokenType::Null => Some(Value::Null),
             TokenType::IntegerLiteral => match self.source.parse::<i128>() {
                 Ok(value) => Some(Value::Integer(value)),
-                Err(_) => None,
+                Err(_) => self.source.parse::<BigInt>().ok().map(Value::from),
             },
             TokenType::FloatLiteral => match self.source.parse::<f64>() {
                 Ok(value) => Some(Value::Float(value)),
                 Err(_) => None,
             },
-            TokenType::StringLiteral => Some(Value::String(
-                self.source
-                    .strip_prefix('"')
-                    .and_then(|s| s.strip_suffix('"'))
-                    // Handling escape sequences
-                    .map(|s| s.replace("\\\"", "\"")) // Use map here
-                    .map(|s| s.replace("\\n", "\n")) // Use map here
-                    .map(|s| s.replace("\\t", "\t")) // Use map here
-                    .unwrap(),
-            )),
-            TokenType::CharLiteral => Some(Value::Char(self.source.chars().nth(1).unwrap())),
+            TokenType::StringLiteral => unescape(&self.source[1..self.source.len() - 1]).map(Value::String),
+            TokenType::CharLiteral => unescape(&self.source[1..self.source.len() - 1])
+                .and_then(|text| text.chars().next())
+                .map(Value::Char),
+            TokenType::BytesLiteral => bytes::decode_literal(&self.source).map(Value::Bytes),
+            TokenType::DateTimeLiteral => self.source.parse::<DateTime>().ok().map(Value::DateTime),
+            TokenType::QuantityLiteral => units::parse_quantity(&self.source).ok(),
             _ => None,
         };
     }
 }
 
+// Decodes the escape sequences of a string or char literal: `\\`, `\"`, `\'`, `\n`, `\r`, `\t`, `\0` and `\u{..}`
+// with one to six hex digits. Other escapes are kept as written. Returns `None` for an invalid `\u{..}` escape.
+pub(crate) fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            't' => unescaped.push('\t'),
+            '0' => unescaped.push('\0'),
+            c @ ('\\' | '"' | '\'') => unescaped.push(c),
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let (hex, rest) = rest.split_once('}')?;
+                if hex.is_empty() || hex.len() > 6 {
+                    return None;
+                }
+                unescaped.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+                chars = rest.chars();
+            }
+            c => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+        }
+    }
+    return Some(unescaped);
+}
+
 impl Display for Token {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         return match self.token_type {
@@ -101,7 +135,19 @@ impl Display for Token {
             TokenType::CharLiteral => {
                 write!(f, "[{}:{}] Char: {}", self.line, self.col, self.source)
             }
+            TokenType::BytesLiteral => {
+                write!(f, "[{}:{}] Bytes: {}", self.line, self.col, self.source)
+            }
+            TokenType::DateTimeLiteral => {
+                write!(f, "[{}:{}] DateTime: {}", self.line, self.col, self.source)
+            }
+            TokenType::QuantityLiteral => {
+                write!(f, "[{}:{}] Quantity: {}", self.line, self.col, self.source)
+            }
             TokenType::Identifier => write!(f, "[{}:{}] Identifier: {}", self.line, self.col, self.source),
+            TokenType::QualifiedIdentifier => {
+                write!(f, "[{}:{}] Qualified identifier: {}", self.line, self.col, self.source)
+            }
             TokenType::Error => write!(f, "[Error] [{}:{}] {}", self.line, self.col, self.source),
             _ => write!(f, "[{}:{}] {:?}", self.line, self.col, self.token_type),
         };
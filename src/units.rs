@@ -0,0 +1,275 @@
+use crate::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::Duration,
+};
+
+// Numbers with a unit suffix, written without a space: durations such as `250ms`, `30s` or `1h30m`, and sizes such
+// as `64KiB` or `2GB`. Fractions are allowed as long as they resolve to whole nanoseconds or bytes: `1.5h`, `0.5KiB`.
+
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+];
+
+const SIZE_UNITS: &[(&str, u128)] = &[
+    ("B", 1),
+    ("KB", 1_000),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+];
+
+// A number of bytes, written with a decimal (`KB`, `MB`, ...) or binary (`KiB`, `MiB`, ...) unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const fn new(bytes: u64) -> Self {
+        return Self(bytes);
+    }
+
+    pub const fn kib(kib: u64) -> Self {
+        return Self(kib << 10);
+    }
+
+    pub const fn mib(mib: u64) -> Self {
+        return Self(mib << 20);
+    }
+
+    pub const fn gib(gib: u64) -> Self {
+        return Self(gib << 30);
+    }
+
+    pub const fn as_u64(&self) -> u64 {
+        return self.0;
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        return Self(bytes);
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        return size.0;
+    }
+}
+
+// Uses whichever unit gives the smallest whole number, so `65536` prints as `64KiB` and `2000000000` as `2GB`.
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0B");
+        }
+        let (unit, factor) = SIZE_UNITS
+            .iter()
+            .filter(|(_, factor)| (self.0 as u128).is_multiple_of(*factor))
+            .max_by_key(|(_, factor)| *factor)
+            .unwrap();
+        return write!(f, "{}{}", self.0 as u128 / factor, unit);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError {
+    pub message: String,
+}
+
+impl Display for ParseUnitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl std::error::Error for ParseUnitError {}
+
+fn invalid(message: String) -> ParseUnitError {
+    return ParseUnitError { message };
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match parse_quantity(s)? {
+            Value::ByteSize(size) => Ok(size),
+            _ => Err(invalid(format!("'{}' is not a size", s))),
+        };
+    }
+}
+
+// Splits the literal into `(number, unit)` components: `1h30m` is `[("1", "h"), ("30", "m")]`.
+fn components(s: &str) -> Result<Vec<(&str, &str)>, ParseUnitError> {
+    let mut components = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let unit_end = rest[number_end..]
+            .find(|c: char| c.is_ascii_digit())
+            .map_or(rest.len(), |i| number_end + i);
+        let (number, unit) = (&rest[..number_end], &rest[number_end..unit_end]);
+        if number.is_empty() || unit.is_empty() {
+            return Err(invalid(format!("'{}' is not a number followed by a unit", s)));
+        }
+        components.push((number, unit));
+        rest = &rest[unit_end..];
+    }
+    return Ok(components);
+}
+
+// The exact value of `number` in units of `factor`, failing if it isn't a whole amount.
+fn scale(number: &str, factor: u128, literal: &str) -> Result<u128, ParseUnitError> {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let overflow = || invalid(format!("'{}' is too large", literal));
+    if integer.is_empty() || fraction.contains('.') || fraction.len() > 18 {
+        return Err(invalid(format!("'{}' is not a valid number", number)));
+    }
+
+    let whole = integer
+        .parse::<u128>()
+        .map_err(|_| overflow())?
+        .checked_mul(factor)
+        .ok_or_else(overflow)?;
+    if fraction.is_empty() {
+        return Ok(whole);
+    }
+    let scaled_fraction = fraction.parse::<u128>().unwrap() * factor;
+    let divisor = 10u128.pow(fraction.len() as u32);
+    if !scaled_fraction.is_multiple_of(divisor) {
+        return Err(invalid(format!(
+            "'{}' is not a whole number of the smallest unit",
+            literal
+        )));
+    }
+    return whole.checked_add(scaled_fraction / divisor).ok_or_else(overflow);
+}
+
+fn unit_factor(units: &[(&str, u128)], unit: &str) -> Option<u128> {
+    return units.iter().find(|(name, _)| *name == unit).map(|(_, factor)| *factor);
+}
+
+// Parses a unit-suffixed literal into a `Value::Duration` or `Value::ByteSize`.
+pub fn parse_quantity(literal: &str) -> Result<Value, ParseUnitError> {
+    let components = components(literal)?;
+    if let [(number, unit)] = components.as_slice()
+        && let Some(factor) = unit_factor(SIZE_UNITS, unit)
+    {
+        let bytes = scale(number, factor, literal)?;
+        let bytes = u64::try_from(bytes).map_err(|_| invalid(format!("'{}' is too large", literal)))?;
+        return Ok(Value::ByteSize(ByteSize(bytes)));
+    }
+
+    let mut nanoseconds = 0u128;
+    for (number, unit) in components {
+        let Some(factor) = unit_factor(DURATION_UNITS, unit) else {
+            return Err(invalid(format!("unknown unit '{}' in '{}'", unit, literal)));
+        };
+        nanoseconds = nanoseconds
+            .checked_add(scale(number, factor, literal)?)
+            .ok_or_else(|| invalid(format!("'{}' is too large", literal)))?;
+    }
+    let seconds =
+        u64::try_from(nanoseconds / 1_000_000_000).map_err(|_| invalid(format!("'{}' is too large", literal)))?;
+    return Ok(Value::Duration(Duration::new(
+        seconds,
+        (nanoseconds % 1_000_000_000) as u32,
+    )));
+}
+
+pub fn parse_duration(literal: &str) -> Result<Duration, ParseUnitError> {
+    return match parse_quantity(literal)? {
+        Value::Duration(duration) => Ok(duration),
+        _ => Err(invalid(format!("'{}' is not a duration", literal))),
+    };
+}
+
+// Writes whole hours, minutes and seconds, then the sub-second part in the coarsest exact unit: `1h30m`, `1s500ms`.
+pub fn format_duration(duration: &Duration) -> String {
+    if duration.is_zero() {
+        return "0s".to_string();
+    }
+
+    let seconds = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    let mut formatted = String::new();
+    for (amount, unit) in [(seconds / 3600, "h"), (seconds % 3600 / 60, "m"), (seconds % 60, "s")] {
+        if amount > 0 {
+            formatted.push_str(&format!("{}{}", amount, unit));
+        }
+    }
+    if nanos > 0 {
+        let (amount, unit) = match nanos {
+            n if n % 1_000_000 == 0 => (n / 1_000_000, "ms"),
+            n if n % 1_000 == 0 => (n / 1_000, "us"),
+            n => (n, "ns"),
+        };
+        formatted.push_str(&format!("{}{}", amount, unit));
+    }
+    return formatted;
+}
+
+// Conversions
+
+impl Serialize for Duration {}
+impl ToSon for Duration {
+    fn to_son(&self) -> Value {
+        return Value::Duration(*self);
+    }
+}
+
+impl Deserialize for Duration {}
+impl FromSon for Duration {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::Duration(d) => Ok(d),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "Duration".to_string(),
+                found: son.get_type(),
+            }),
+        };
+    }
+}
+
+impl Serialize for ByteSize {}
+impl ToSon for ByteSize {
+    fn to_son(&self) -> Value {
+        return Value::ByteSize(*self);
+    }
+}
+
+// A plain integer is read as a number of bytes.
+impl Deserialize for ByteSize {}
+impl FromSon for ByteSize {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::ByteSize(size) => Ok(size),
+            Value::Integer(i) => u64::try_from(i)
+                .map(ByteSize)
+                .map_err(|_| DeserializationError::InvalidValue {
+                    message: format!("{} is not a valid number of bytes", i),
+                }),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "ByteSize".to_string(),
+                found: son.get_type(),
+            }),
+        };
+    }
+}
@@ -1,18 +1,38 @@
-use crate::{DeserializationError, Deserialize, FromSon, Printer, Serialize, ToSon};
+use crate::{
+    BigInt, ByteSize, DateTime, DeserializationError, Deserialize, EnumVariant, FromSon, Number, Path, PathSegment,
+    Printer, PrinterOptions, RawSon, Serialize, ToSon,
+};
 use std::{
     collections::HashMap,
+    convert::Infallible,
     fmt::{Display, Formatter},
+    time::Duration,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+// `PartialEq`, `Eq`, `Hash` and `Ord` are implemented in `compare.rs`, which documents the order across variants.
+#[derive(Debug, Clone)]
 pub enum Value {
     Null,
     Bool(bool),
     Float(f64),
     Integer(i128),
+    // Only holds integers outside of the `i128` range, smaller ones are always stored as `Integer`.
+    BigInteger(BigInt),
+    // A number kept as written in the source, only produced when parsing with `preserve_numbers`.
+    Number(Number),
     String(String),
     Char(char),
-    Enum(String),
+    // A variant name, optionally qualified with its enum type as `Status.Active`.
+    Enum(EnumVariant),
+    // Binary data, written as a `b"..."` or `b64"..."` literal.
+    Bytes(Vec<u8>),
+    // An RFC 3339 date, time, or date time, written unquoted.
+    DateTime(DateTime),
+    // Unit-suffixed literals such as `1h30m` and `64KiB`.
+    Duration(Duration),
+    ByteSize(ByteSize),
+    // Unparsed source text, produced for `RawSon` fields and printed verbatim.
+    Raw(RawSon),
     Array(Vec<Value>),
     Object(HashMap<String, Value>),
 }
@@ -24,9 +44,16 @@ impl Value {
             Value::Bool(_) => "Bool".to_string(),
             Value::Float(_) => "Float".to_string(),
             Value::Integer(_) => "Integer".to_string(),
+            Value::BigInteger(_) => "BigInteger".to_string(),
+            Value::Number(_) => "Number".to_string(),
             Value::String(_) => "String".to_string(),
             Value::Char(_) => "Char".to_string(),
             Value::Enum(_) => "Enum".to_string(),
+            Value::Bytes(_) => "Bytes".to_string(),
+            Value::DateTime(_) => "DateTime".to_string(),
+            Value::Duration(_) => "Duration".to_string(),
+            Value::ByteSize(_) => "ByteSize".to_string(),
+            Value::Raw(_) => "Raw".to_string(),
             Value::Array(_) => "Array".to_string(),
             Value::Object(_) => "Object".to_string(),
         };
@@ -36,12 +63,233 @@ impl Value {
         return match self {
             Value::Bool(b) => Value::Bool(!b),
             Value::Float(f) => Value::Float(-f),
-            Value::Integer(i) => Value::Integer(-i),
+            Value::Integer(i) => match i.checked_neg() {
+                Some(negated) => Value::Integer(negated),
+                None => Value::from(-BigInt::from(*i)),
+            },
+            Value::BigInteger(b) => Value::from(-b.clone()),
+            Value::Number(n) => Value::Number(n.negate()),
             _ => self.clone(),
         };
     }
 }
 
+// Traversal
+//
+// All traversals are pre-order, visit array items by index and object entries sorted by key, and pass the path of the
+// current value from the root. Leaves are every value that isn't an array or an object.
+
+// Returned by `walk` and `walk_mut` callbacks to control the traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    Continue,
+    // Don't visit the children of the current value, but continue with its siblings.
+    SkipChildren,
+    Stop,
+}
+
+fn sorted_keys(object: &HashMap<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = object.keys().cloned().collect();
+    keys.sort();
+    return keys;
+}
+
+fn walk_inner<F>(value: &Value, path: &mut Path, visit: &mut F) -> bool
+where
+    F: FnMut(&Path, &Value) -> Walk,
+{
+    match visit(path, value) {
+        Walk::Continue => (),
+        Walk::SkipChildren => return true,
+        Walk::Stop => return false,
+    }
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                let completed = walk_inner(item, path, visit);
+                path.pop();
+                if !completed {
+                    return false;
+                }
+            }
+        }
+        Value::Object(object) => {
+            for key in sorted_keys(object) {
+                let child = &object[&key];
+                path.push(PathSegment::Key(key));
+                let completed = walk_inner(child, path, visit);
+                path.pop();
+                if !completed {
+                    return false;
+                }
+            }
+        }
+        _ => (),
+    }
+    return true;
+}
+
+fn walk_mut_inner<F>(value: &mut Value, path: &mut Path, visit: &mut F) -> bool
+where
+    F: FnMut(&Path, &mut Value) -> Walk,
+{
+    match visit(path, value) {
+        Walk::Continue => (),
+        Walk::SkipChildren => return true,
+        Walk::Stop => return false,
+    }
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                path.push(PathSegment::Index(i));
+                let completed = walk_mut_inner(item, path, visit);
+                path.pop();
+                if !completed {
+                    return false;
+                }
+            }
+        }
+        Value::Object(object) => {
+            for key in sorted_keys(object) {
+                let child = object.get_mut(&key).unwrap();
+                path.push(PathSegment::Key(key));
+                let completed = walk_mut_inner(child, path, visit);
+                path.pop();
+                if !completed {
+                    return false;
+                }
+            }
+        }
+        _ => (),
+    }
+    return true;
+}
+
+fn try_map_inner<E, F>(value: Value, path: &mut Path, map: &mut F) -> Result<Value, E>
+where
+    F: FnMut(&Path, Value) -> Result<Value, E>,
+{
+    return match value {
+        Value::Array(items) => {
+            let mut mapped = Vec::with_capacity(items.len());
+            for (i, item) in items.into_iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                let result = try_map_inner(item, path, map);
+                path.pop();
+                mapped.push(result?);
+            }
+            Ok(Value::Array(mapped))
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut mapped = HashMap::with_capacity(entries.len());
+            for (key, item) in entries {
+                path.push(PathSegment::Key(key.clone()));
+                let result = try_map_inner(item, path, map);
+                path.pop();
+                mapped.insert(key, result?);
+            }
+            Ok(Value::Object(mapped))
+        }
+        leaf => map(path, leaf),
+    };
+}
+
+impl Value {
+    // Calls `visit` for this value and every value nested in it. Returns false if the walk was stopped.
+    pub fn walk<F>(&self, mut visit: F) -> bool
+    where
+        F: FnMut(&Path, &Value) -> Walk,
+    {
+        return walk_inner(self, &mut Path::root(), &mut visit);
+    }
+
+    // Like `walk`, but `visit` may change each value, and the children of the changed value are visited afterwards.
+    pub fn walk_mut<F>(&mut self, mut visit: F) -> bool
+    where
+        F: FnMut(&Path, &mut Value) -> Walk,
+    {
+        return walk_mut_inner(self, &mut Path::root(), &mut visit);
+    }
+
+    // Replaces every leaf with the result of `map`, keeping the structure of arrays and objects.
+    pub fn map_leaves<F>(self, mut map: F) -> Value
+    where
+        F: FnMut(&Path, Value) -> Value,
+    {
+        let result = try_map_inner::<Infallible, _>(self, &mut Path::root(), &mut |path, leaf| Ok(map(path, leaf)));
+        return match result {
+            Ok(value) => value,
+        };
+    }
+
+    // Like `map_leaves`, but stops at the first error.
+    pub fn try_map<E, F>(self, mut map: F) -> Result<Value, E>
+    where
+        F: FnMut(&Path, Value) -> Result<Value, E>,
+    {
+        return try_map_inner(self, &mut Path::root(), &mut map);
+    }
+
+    // Combines this value and every value nested in it, in traversal order.
+    pub fn fold<B, F>(&self, init: B, mut combine: F) -> B
+    where
+        F: FnMut(B, &Path, &Value) -> B,
+    {
+        let mut accumulator = Some(init);
+        self.walk(|path, value| {
+            accumulator = Some(combine(accumulator.take().unwrap(), path, value));
+            return Walk::Continue;
+        });
+        return accumulator.unwrap();
+    }
+
+    // Iterates over this value and every value nested in it, together with their paths.
+    pub fn iter_paths(&self) -> PathIter<'_> {
+        return PathIter {
+            stack: vec![(Path::root(), self)],
+        };
+    }
+}
+
+pub struct PathIter<'a> {
+    // Values still to visit, with the next one last.
+    stack: Vec<(Path, &'a Value)>,
+}
+
+impl<'a> Iterator for PathIter<'a> {
+    type Item = (Path, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, value) = self.stack.pop()?;
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate().rev() {
+                    self.stack.push((path.index(i), item));
+                }
+            }
+            Value::Object(object) => {
+                for key in sorted_keys(object).iter().rev() {
+                    self.stack.push((path.key(key), &object[key]));
+                }
+            }
+            _ => (),
+        }
+        return Some((path, value));
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        return match value.to_i128() {
+            Some(i) => Value::Integer(i),
+            None => Value::BigInteger(value),
+        };
+    }
+}
+
 impl Deserialize for Value {}
 impl FromSon for Value {
     fn from_son(son: Value) -> Result<Self, DeserializationError> {
@@ -58,7 +306,7 @@ impl ToSon for Value {
 
 impl Display for Value {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let printer = Printer::new("    ".to_string());
-        write!(f, "{}", printer.son_to_string(&self))
+        let printer = Printer::with_options(PrinterOptions::new());
+        write!(f, "{}", printer.son_to_string(self))
     }
 }
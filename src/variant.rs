@@ -0,0 +1,160 @@
+use crate::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
+
+// An enum variant name with the optional type it was qualified with, written `Status.Active` or
+// `module::Status::Active`.
+//
+// Variants compare by type name, then by name, and unqualified variants sort first. So `Status.Active` equals
+// `accounts::Status::Active`, but neither `Active` nor `Mode.Active`. Derived `FromSon` implementations are more
+// lenient and also accept the unqualified name.
+//
+// `true`, `false`, `null`, `inf` and `NaN` are keywords, which are reserved: a variant with one of these names is only
+// read back as a variant when it's qualified, as in `Float.NaN`. Unqualified, the printer writes it as is and it reads
+// back as the keyword's value. Derived `ToSon` implementations always qualify their variants.
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    qualifier: Option<String>,
+    name: String,
+}
+
+impl EnumVariant {
+    pub fn new(name: &str) -> EnumVariant {
+        return EnumVariant {
+            qualifier: None,
+            name: name.to_string(),
+        };
+    }
+
+    // `qualifier` is a type name, optionally with its module path: `Status` or `module::Status`.
+    pub fn qualified(qualifier: &str, name: &str) -> EnumVariant {
+        return EnumVariant {
+            qualifier: Some(qualifier.to_string()),
+            name: name.to_string(),
+        };
+    }
+
+    // Builds a variant from an identifier as written in the source, which the lexer already validated.
+    pub(crate) fn from_source(source: &str) -> EnumVariant {
+        return match split_source(source) {
+            (Some(qualifier), name) => EnumVariant::qualified(qualifier, name),
+            (None, name) => EnumVariant::new(name),
+        };
+    }
+
+    pub(crate) fn heap_size(&self) -> usize {
+        return self.name.capacity() + self.qualifier.as_ref().map_or(0, String::capacity);
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn qualifier(&self) -> Option<&str> {
+        return self.qualifier.as_deref();
+    }
+
+    // The last segment of the qualifier: `Status` for both `Status.Active` and `module::Status::Active`.
+    pub fn type_name(&self) -> Option<&str> {
+        return self
+            .qualifier
+            .as_deref()
+            .map(|qualifier| qualifier.rsplit("::").next().unwrap_or(qualifier));
+    }
+}
+
+// Splits `Status.Active` and `module::Status::Active` into the qualifier and the variant name.
+pub(crate) fn split_source(source: &str) -> (Option<&str>, &str) {
+    if let Some((qualifier, name)) = source.rsplit_once("::") {
+        return (Some(qualifier), name);
+    }
+    if let Some((qualifier, name)) = source.split_once('.') {
+        return (Some(qualifier), name);
+    }
+    return (None, source);
+}
+
+impl From<&str> for EnumVariant {
+    fn from(name: &str) -> Self {
+        return EnumVariant::new(name);
+    }
+}
+
+impl From<String> for EnumVariant {
+    fn from(name: String) -> Self {
+        return EnumVariant { qualifier: None, name };
+    }
+}
+
+impl PartialEq for EnumVariant {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for EnumVariant {}
+
+impl PartialEq<str> for EnumVariant {
+    fn eq(&self, other: &str) -> bool {
+        return self.name == other;
+    }
+}
+
+impl PartialEq<&str> for EnumVariant {
+    fn eq(&self, other: &&str) -> bool {
+        return self.name == *other;
+    }
+}
+
+impl Hash for EnumVariant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_name().hash(state);
+        self.name.hash(state);
+    }
+}
+
+impl PartialOrd for EnumVariant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for EnumVariant {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return (self.type_name(), &self.name).cmp(&(other.type_name(), &other.name));
+    }
+}
+
+// Writes the qualified form. Module paths use `::` throughout, a bare type name uses `.`.
+impl Display for EnumVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match &self.qualifier {
+            Some(qualifier) if qualifier.contains("::") => write!(f, "{}::{}", qualifier, self.name),
+            Some(qualifier) => write!(f, "{}.{}", qualifier, self.name),
+            None => write!(f, "{}", self.name),
+        };
+    }
+}
+
+impl Serialize for EnumVariant {}
+impl ToSon for EnumVariant {
+    fn to_son(&self) -> Value {
+        return Value::Enum(self.clone());
+    }
+}
+
+impl Deserialize for EnumVariant {}
+impl FromSon for EnumVariant {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return match son {
+            Value::Enum(variant) => Ok(variant),
+            _ => Err(DeserializationError::UnexpectedType {
+                expected: "Enum".to_string(),
+                found: son.get_type(),
+            }),
+        };
+    }
+}
@@ -0,0 +1,108 @@
+use son::{MismatchError, Path, Serialize, ToSon, Tolerance, Value, from_str, son, to_string};
+
+#[derive(Serialize)]
+struct Sample {
+    time: f64,
+    position: Vec<f64>,
+}
+
+#[test]
+fn test_round_trip_within_tolerance() {
+    let computed = Sample {
+        time: 0.1 + 0.2,
+        position: vec![1.0 / 3.0, 2.0 / 3.0],
+    };
+    let expected = son!({ time: 0.3 position: [0.3333333333333333 0.6666666666666666] });
+    let round_trip: Value = from_str(&to_string(&computed)).unwrap();
+
+    assert!(round_trip.approx_eq(&expected, Tolerance::new()).is_err());
+    assert!(round_trip.approx_eq(&expected, Tolerance::new().ulps(1)).is_ok());
+    assert!(round_trip.approx_eq(&expected, Tolerance::new().abs(1e-12)).is_ok());
+    assert!(round_trip.approx_eq(&expected, Tolerance::new().rel(1e-15)).is_ok());
+}
+
+#[test]
+fn test_tolerance_bounds() {
+    let close = |a: f64, b: f64, tolerance: Tolerance| Value::Float(a).approx_eq(&Value::Float(b), tolerance).is_ok();
+
+    assert!(close(100.0, 100.5, Tolerance::new().abs(0.5)));
+    assert!(!close(100.0, 100.6, Tolerance::new().abs(0.5)));
+    assert!(close(100.0, 101.0, Tolerance::new().rel(0.01)));
+    assert!(!close(100.0, 102.0, Tolerance::new().rel(0.01)));
+    assert!(close(1.0, 1.0 + 2.0 * f64::EPSILON, Tolerance::new().ulps(2)));
+    assert!(!close(1.0, 1.0 + 3.0 * f64::EPSILON, Tolerance::new().ulps(2)));
+    assert!(close(-0.0, f64::from_bits(1), Tolerance::new().ulps(1)));
+
+    let loose = Tolerance {
+        abs: 1e9,
+        rel: 1.0,
+        ulps: u64::MAX,
+        ..Default::default()
+    };
+    assert!(close(f64::NAN, f64::NAN, loose));
+    assert!(close(f64::INFINITY, f64::INFINITY, loose));
+    assert!(!close(f64::INFINITY, f64::MAX, loose));
+    assert!(!close(f64::NAN, 0.0, loose));
+}
+
+#[test]
+fn test_integers_as_floats() {
+    let a = son!({ count: 1 mean: 2.5 });
+    let b = son!({ count: 1.0 mean: 2.5 });
+
+    let error = a.approx_eq(&b, Tolerance::new()).unwrap_err();
+    assert_eq!(error.path, "$.count".parse::<Path>().unwrap());
+    assert_eq!(error.message, "1 != 1.0");
+
+    assert!(a.approx_eq(&b, Tolerance::new().integers_as_floats(true)).is_ok());
+    assert!(
+        son!([1])
+            .approx_eq(&son!([1.5]), Tolerance::new().integers_as_floats(true).abs(0.5))
+            .is_ok()
+    );
+    assert!(
+        son!([1])
+            .approx_eq(&son!(["1"]), Tolerance::new().integers_as_floats(true))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_reports_first_difference() {
+    let a = son!({ b: { x: 1.0 y: [1.0 2.0 3.0] } a: "same" c: 9.0 });
+    let b = son!({ b: { x: 1.0 y: [1.0 2.5 3.5] } a: "same" c: 0.0 });
+
+    let error = a.approx_eq(&b, Tolerance::new().abs(0.1)).unwrap_err();
+    assert_eq!(
+        error,
+        MismatchError {
+            path: "$.b.y[1]".parse().unwrap(),
+            message: "2.0 != 2.5".to_string(),
+        }
+    );
+    assert_eq!(error.to_string(), "Values differ at $.b.y[1]: 2.0 != 2.5");
+}
+
+#[test]
+fn test_structural_differences() {
+    let tolerance = Tolerance::new().abs(1.0);
+
+    let error = son!([1.0 2.0]).approx_eq(&son!([1.0 2.0 3.0]), tolerance).unwrap_err();
+    assert_eq!(error.path, "$[2]".parse::<Path>().unwrap());
+    assert_eq!(error.message, "missing from the left array");
+
+    let error = son!({ a: 1 b: 2 }).approx_eq(&son!({ b: 2 }), tolerance).unwrap_err();
+    assert_eq!(error.path, "$.a".parse::<Path>().unwrap());
+    assert_eq!(error.message, "missing from the right object");
+
+    let error = son!({ a: [1.0] })
+        .approx_eq(&son!({ a: { x: 1.0 } }), tolerance)
+        .unwrap_err();
+    assert_eq!(error.path, "$.a".parse::<Path>().unwrap());
+
+    assert!(
+        son!({ mode: Fast })
+            .approx_eq(&son!({ mode: Slow }), tolerance)
+            .is_err()
+    );
+}
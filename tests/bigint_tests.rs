@@ -0,0 +1,112 @@
+use son::{BigInt, DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value, son};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Identifiers {
+    hash: u128,
+    small: u128,
+    signed: i128,
+}
+
+#[test]
+fn test_big_int_parse_and_display() {
+    let cases = [
+        "0",
+        "1",
+        "-1",
+        "4294967296",
+        "170141183460469231731687303715884105727",
+        "-170141183460469231731687303715884105728",
+        "340282366920938463463374607431768211455",
+        "123456789012345678901234567890123456789012345678901234567890",
+        "-1000000000000000000000000000000000000000000",
+    ];
+    for case in cases {
+        assert_eq!(case.parse::<BigInt>().unwrap().to_string(), case);
+    }
+
+    assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::zero());
+    assert_eq!("0000123".parse::<BigInt>().unwrap().to_string(), "123");
+    assert!("".parse::<BigInt>().is_err());
+    assert!("12a".parse::<BigInt>().is_err());
+    assert!("1.5".parse::<BigInt>().is_err());
+}
+
+#[test]
+fn test_big_int_conversions() {
+    assert_eq!(BigInt::from(u128::MAX).to_u128(), Some(u128::MAX));
+    assert_eq!(BigInt::from(u128::MAX).to_i128(), None);
+    assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(BigInt::from(i128::MIN).to_u128(), None);
+    assert_eq!(BigInt::from(-5i128).to_string(), "-5");
+    assert_eq!(BigInt::from(u128::MAX).to_f64(), u128::MAX as f64);
+    assert_eq!((-BigInt::from(7i128)).to_i128(), Some(-7));
+
+    let mut sorted: Vec<BigInt> = ["5", "-100000000000000000000000000000000000000000", "0", "-3", "1"]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    sorted.sort();
+    let sorted: Vec<String> = sorted.iter().map(|b| b.to_string()).collect();
+    assert_eq!(
+        sorted,
+        vec!["-100000000000000000000000000000000000000000", "-3", "0", "1", "5"]
+    );
+}
+
+#[test]
+fn test_big_integer_literals() {
+    let value = son::from_str::<Value>(
+        r#"{
+            big: 123456789012345678901234567890123456789012345678901234567890
+            negative_big: -123456789012345678901234567890123456789012345678901234567890
+            max_u128: 340282366920938463463374607431768211455
+            min_i128: -170141183460469231731687303715884105728
+        }"#,
+    )
+    .unwrap();
+
+    let get = |key: &str| value.get_path(&son::Path::root().key(key)).unwrap().clone();
+    assert_eq!(get("big").get_type(), "BigInteger");
+    assert_eq!(
+        get("negative_big"),
+        Value::from(
+            "-123456789012345678901234567890123456789012345678901234567890"
+                .parse::<BigInt>()
+                .unwrap()
+        )
+    );
+    assert_eq!(get("max_u128"), Value::BigInteger(BigInt::from(u128::MAX)));
+    // Numbers that fit in an i128 are always normalized to `Integer`.
+    assert_eq!(get("min_i128"), Value::Integer(i128::MIN));
+    assert_eq!(Value::Integer(i128::MIN).negate().negate(), Value::Integer(i128::MIN));
+
+    let reparsed = son::from_str::<Value>(&value.to_string()).unwrap();
+    assert_eq!(reparsed, value);
+
+    assert_eq!(son!(340282366920938463463374607431768211455), get("max_u128"));
+}
+
+#[test]
+fn test_u128_serialization_deserialization() {
+    let data = Identifiers {
+        hash: u128::MAX - 1,
+        small: 42,
+        signed: i128::MIN,
+    };
+    assert_eq!(data.hash.to_son().get_type(), "BigInteger");
+    assert_eq!(data.small.to_son(), Value::Integer(42));
+
+    let son_string = son::to_string(&data);
+    let deserialized: Identifiers = son::from_str(&son_string).unwrap();
+    assert_eq!(deserialized, data);
+
+    let negative = u128::from_son(Value::Integer(-1));
+    assert!(matches!(negative, Err(DeserializationError::InvalidValue { .. })));
+
+    let too_big = "340282366920938463463374607431768211456".parse::<BigInt>().unwrap();
+    let result = u128::from_son(Value::from(too_big));
+    assert!(matches!(result, Err(DeserializationError::InvalidValue { .. })));
+
+    let result = son::from_str::<Identifiers>("{ hash: -5 small: 1 signed: 1 }");
+    assert!(result.is_err());
+}
@@ -0,0 +1,91 @@
+use son::{BorrowedParser, BorrowedValue, ParseOptions, Path, Value, from_str_borrowed};
+use std::borrow::Cow;
+
+const DOCUMENT: &str = r#"{
+    // Comments and whitespace are skipped as usual.
+    service: "billing"
+    status: Active
+    database: { host: "db.internal" port: 5432 }
+    tags: ["é-commerce" "payments"]
+    limits: { timeout: 30s memory: 512MiB }
+}"#;
+
+fn is_borrowed(value: &BorrowedValue) -> bool {
+    return matches!(
+        value,
+        BorrowedValue::String(Cow::Borrowed(_)) | BorrowedValue::Enum(Cow::Borrowed(_))
+    );
+}
+
+#[test]
+fn test_strings_and_keys_borrow_from_the_input() {
+    let value = from_str_borrowed(DOCUMENT).unwrap();
+
+    let service = value.get("service").unwrap();
+    assert_eq!(service.as_str(), Some("billing"));
+    assert!(is_borrowed(service));
+    assert!(is_borrowed(value.get("status").unwrap()));
+
+    let host = value.get("database").unwrap().get("host").unwrap();
+    assert!(is_borrowed(host));
+    // The borrowed text points into the input rather than at a copy.
+    let input_range = DOCUMENT.as_bytes().as_ptr_range();
+    assert!(input_range.contains(&host.as_str().unwrap().as_ptr()));
+
+    let BorrowedValue::Object(object) = &value else {
+        panic!("expected an object");
+    };
+    assert!(object.keys().all(|key| matches!(key, Cow::Borrowed(_))));
+
+    let tag = value.get("tags").unwrap().get_index(0).unwrap();
+    assert_eq!(tag.as_str(), Some("é-commerce"));
+    assert!(is_borrowed(tag));
+}
+
+#[test]
+fn test_escaped_strings_are_decoded() {
+    let value = from_str_borrowed(r#"["plain" "say \"hi\"" "line\nbreak"]"#).unwrap();
+    assert!(is_borrowed(value.get_index(0).unwrap()));
+
+    let quoted = value.get_index(1).unwrap();
+    assert_eq!(quoted, &BorrowedValue::String(Cow::Owned("say \"hi\"".to_string())));
+    assert_eq!(value.get_index(2).unwrap().as_str(), Some("line\nbreak"));
+}
+
+#[test]
+fn test_into_owned_matches_the_owned_parser() {
+    let owned: Value = son::from_str(DOCUMENT).unwrap();
+    assert_eq!(from_str_borrowed(DOCUMENT).unwrap().into_owned(), owned);
+
+    let numbers = "[1 -2 -3.5 -true 170141183460469231731687303715884105728 'c' b\"\\x01\" 2024-01-15]";
+    let owned: Value = son::from_str(numbers).unwrap();
+    assert_eq!(from_str_borrowed(numbers).unwrap().into_owned(), owned);
+
+    let owned: Value = son::from_str_with(numbers, ParseOptions::new().preserve_numbers(true)).unwrap();
+    let mut parser = BorrowedParser::with_options(numbers, ParseOptions::new().preserve_numbers(true));
+    assert_eq!(parser.parse().unwrap().into_owned(), owned);
+
+    let options = ParseOptions::new().raw_paths(vec![Path::root().key("database"), Path::root().key("tags").index(1)]);
+    let owned: Value = son::from_str_with(DOCUMENT, options.clone()).unwrap();
+    let borrowed = BorrowedParser::with_options(DOCUMENT, options).parse().unwrap();
+    assert_eq!(borrowed.get("database").unwrap().get_type(), "Raw");
+    assert_eq!(borrowed.into_owned(), owned);
+}
+
+#[test]
+fn test_from_owned_value() {
+    let owned: Value = son::from_str(DOCUMENT).unwrap();
+    let borrowed = BorrowedValue::from(owned.clone());
+    assert_eq!(borrowed.get("service").unwrap().as_str(), Some("billing"));
+    assert!(!is_borrowed(borrowed.get("service").unwrap()));
+    assert_eq!(borrowed.into_owned(), owned);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(from_str_borrowed("\"not a document\"").is_err());
+    assert!(from_str_borrowed("{ name: \"unterminated }").is_err());
+    assert!(from_str_borrowed("[1 2").is_err());
+    assert!(from_str_borrowed("{ : 1 }").is_err());
+    assert_eq!(from_str_borrowed("{}").unwrap().get_type(), "Object");
+}
@@ -0,0 +1,101 @@
+use son::{Bytes, DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value, son};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Attachment {
+    name: String,
+    payload: Bytes,
+    checksum: Vec<u8>,
+}
+
+fn field(value: &Value, key: &str) -> Value {
+    return value.get_path(&son::Path::root().key(key)).unwrap().clone();
+}
+
+#[test]
+fn test_parse_byte_string_literals() {
+    let value = son::from_str::<Value>(
+        r#"{
+            text: b"GIF89a\x00\x01\n"
+            quoted: b"say \"hi\"\\"
+            encoded: b64"3q2+7w=="
+            unpadded: b64"3q2+7w"
+            empty: b""
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(field(&value, "text"), Value::Bytes(b"GIF89a\x00\x01\n".to_vec()));
+    assert_eq!(field(&value, "quoted"), Value::Bytes(b"say \"hi\"\\".to_vec()));
+    assert_eq!(field(&value, "encoded"), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(field(&value, "unpadded"), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(field(&value, "empty"), Value::Bytes(Vec::new()));
+    assert_eq!(field(&value, "text").get_type(), "Bytes");
+}
+
+#[test]
+fn test_identifiers_starting_with_b_are_still_enums() {
+    let value = son::from_str::<Value>("{ a: b c: b64 d: bytes }").unwrap();
+    assert_eq!(field(&value, "a"), Value::Enum("b".into()));
+    assert_eq!(field(&value, "c"), Value::Enum("b64".into()));
+    assert_eq!(field(&value, "d"), Value::Enum("bytes".into()));
+}
+
+#[test]
+fn test_invalid_byte_string_literals() {
+    assert!(son::from_str::<Value>(r#"{ a: b"\q" }"#).is_err());
+    assert!(son::from_str::<Value>(r#"{ a: b"\x4" }"#).is_err());
+    assert!(son::from_str::<Value>(r#"{ a: b"é" }"#).is_err());
+    assert!(son::from_str::<Value>(r#"{ a: b64"a" }"#).is_err());
+    assert!(son::from_str::<Value>(r#"{ a: b64"ab!d" }"#).is_err());
+    for padded in ["ABC==", "AB=", "ABCD=", "ABCD====", "A==="] {
+        assert!(son::bytes::decode_base64(padded).is_none(), "{}", padded);
+    }
+    assert_eq!(son::bytes::decode_base64("AB=="), Some(vec![0]));
+    assert_eq!(son::bytes::decode_base64("ABC="), Some(vec![0, 16]));
+    assert!(son::from_str::<Value>(r#"{ a: b"open }"#).is_err());
+}
+
+#[test]
+fn test_bytes_print_and_round_trip() {
+    let value = son! {
+        text: b"line\n\"quoted\""
+        binary: #(Value::Bytes((0..=255).collect()))
+        empty: b""
+    };
+
+    let printed = value.to_string();
+    assert!(printed.contains(r#"text: b"line\n\"quoted\"""#));
+    assert!(printed.contains("binary: b64\"AAECAwQF"));
+    assert!(printed.contains("empty: b\"\"\n"));
+
+    let reparsed = son::from_str::<Value>(&printed).unwrap();
+    assert_eq!(reparsed, value);
+}
+
+#[test]
+fn test_serialize_and_deserialize_bytes() {
+    let attachment = Attachment {
+        name: "logo.png".to_string(),
+        payload: Bytes::from(vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a]),
+        checksum: vec![1, 2, 3],
+    };
+
+    let value = son::to_son(&attachment);
+    assert_eq!(field(&value, "payload"), Value::Bytes(attachment.payload.to_vec()));
+    assert_eq!(field(&value, "checksum"), Value::Bytes(vec![1, 2, 3]));
+    assert_eq!(son::to_string(&vec![1u8, 2, 3]), r#"b64"AQID""#);
+    assert_eq!(son::from_str::<Vec<u8>>(r#"b64"AQID""#).unwrap(), vec![1, 2, 3]);
+    assert_eq!(son::to_string(&vec![1u16, 2, 3]), "[1,2,3]");
+
+    let printed = son::to_string(&attachment);
+    assert_eq!(son::from_str::<Attachment>(&printed).unwrap(), attachment);
+
+    // Both representations are accepted when deserializing.
+    let parsed: Attachment = son::from_str(r#"{ name: "x" payload: [1 2] checksum: b"\x01\x02" }"#).unwrap();
+    assert_eq!(parsed.payload, Bytes::new(vec![1, 2]));
+    assert_eq!(parsed.checksum, vec![1, 2]);
+
+    assert!(son::from_str::<Vec<String>>(r#"[ "a" ]"#).is_ok());
+    assert!(son::from_str::<Value>(r#"[ b"a" ]"#).is_ok());
+    assert!(<Vec<String>>::from_son(Value::Bytes(vec![1])).is_err());
+}
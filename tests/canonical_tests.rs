@@ -0,0 +1,97 @@
+use son::{
+    DeserializationError, Deserialize, FromSon, Number, ParseOptions, Serialize, ToSon, Value, from_str, from_str_with,
+    son, to_canonical_string,
+};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    name: String,
+    retries: i64,
+    ratio: f64,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_canonical_form() {
+    let value: Value = from_str(
+        r#"{
+        // Comments and layout don't matter.
+        zeta: [1 2.5 -0.0]
+        alpha: { b: true a: null }
+        text: "say \"hi\"\n"
+        letter: 'x'
+        mode: Fast
+    }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        value.to_canonical_string(),
+        r#"{alpha: {a: null, b: true}, letter: 'x', mode: Fast, text: "say \"hi\"\n", zeta: [1, 2.5, 0.0]}"#
+    );
+    assert_eq!(Value::Char('\'').to_canonical_string(), "'\\''");
+    assert_eq!(son!([]).to_canonical_string(), "[]");
+    assert_eq!(son!({}).to_canonical_string(), "{}");
+}
+
+#[test]
+fn test_to_canonical_string_for_types() {
+    let config = Config {
+        name: "api".to_string(),
+        retries: 3,
+        ratio: 1.0,
+        tags: vec!["a".to_string(), "b".to_string()],
+    };
+    assert_eq!(
+        to_canonical_string(&config),
+        r#"{name: "api", ratio: 1.0, retries: 3, tags: ["a", "b"]}"#
+    );
+
+    let parsed: Value = from_str(&to_canonical_string(&config)).unwrap();
+    assert_eq!(parsed, son::to_son(&config));
+}
+
+#[test]
+fn test_numbers_are_normalized() {
+    let number = |literal: &str| Value::Number(Number::new(literal).unwrap()).to_canonical_string();
+    assert_eq!(number("19.10"), "19.1");
+    assert_eq!(number("007"), "7");
+    assert_eq!(number("-0.000"), "0.0");
+    assert_eq!(number("3.0"), "3.0");
+    assert_eq!(number("-0.50"), "-0.5");
+
+    assert_eq!(Value::Float(0.1).to_canonical_string(), "0.1");
+    assert_eq!(Value::Float(1e21).to_canonical_string(), "1000000000000000000000.0");
+    assert_eq!(Value::Float(f64::NAN).to_canonical_string(), "NaN");
+
+    let preserved: Value = from_str_with("[1.50]", ParseOptions::new().preserve_numbers(true)).unwrap();
+    let plain: Value = from_str("[1.5]").unwrap();
+    assert_eq!(preserved.to_canonical_string(), plain.to_canonical_string());
+}
+
+#[test]
+fn test_unusual_keys_and_control_characters() {
+    let mut object = HashMap::new();
+    object.insert("max connections".to_string(), Value::Integer(1));
+    object.insert("tab".to_string(), Value::String("a\tb\u{7}".to_string()));
+    assert_eq!(
+        Value::Object(object).to_canonical_string(),
+        r#"{"max connections": 1, tab: "a\tb\u{7}"}"#
+    );
+}
+
+#[test]
+fn test_fingerprint_ignores_formatting_and_key_order() {
+    let a: Value = from_str("{ b: [1 2] a: { y: 1.0 x: \"s\" } }").unwrap();
+    let b: Value =
+        from_str("{\n    // Same config, different layout.\n    a: { x: \"s\", y: 1.0 },\n    b: [1, 2]\n}").unwrap();
+    assert_eq!(a.fingerprint(), b.fingerprint());
+    assert_eq!(a.fingerprint(), son::canonical::fingerprint(&a));
+
+    let c: Value = from_str("{ b: [2 1] a: { y: 1.0 x: \"s\" } }").unwrap();
+    assert_ne!(a.fingerprint(), c.fingerprint());
+    assert_ne!(son!([1]).fingerprint(), son!([1.0]).fingerprint());
+
+    // Documented as FNV-1a over the canonical form, so fingerprints never change between versions.
+    assert_eq!(son!([]).fingerprint(), 0x09612b07b5ecb5a5);
+}
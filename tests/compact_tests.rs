@@ -0,0 +1,124 @@
+use son::{CompactDocument, Path, Value, from_str, son};
+use std::time::Duration;
+
+fn records(count: usize) -> String {
+    let mut input = "[\n".to_string();
+    for i in 0..count {
+        input.push_str(&format!(
+            "{{ id: {} name: \"user-{}\" email: \"user-{}@example.com\" status: Active score: {}.5 }}\n",
+            i, i, i, i
+        ));
+    }
+    input.push(']');
+    return input;
+}
+
+#[test]
+fn test_accessors() {
+    let document = CompactDocument::parse(
+        r#"{
+        service: "billing-service-with-a-long-name"
+        region: EuWest
+        port: 8080
+        ratio: 0.75
+        enabled: true
+        initial: 'b'
+        timeout: 30s
+        huge: 170141183460469231731687303715884105727
+        replicas: [{ zone: "a" } { zone: "b" }]
+    }"#,
+    )
+    .unwrap();
+    let root = document.root();
+
+    assert_eq!(root.get_type(), "Object");
+    assert_eq!(root.len(), 9);
+    assert_eq!(
+        root.get("service").unwrap().as_str(),
+        Some("billing-service-with-a-long-name")
+    );
+    assert_eq!(root.get("region").unwrap().as_str(), Some("EuWest"));
+    assert_eq!(root.get("region").unwrap().get_type(), "Enum");
+    assert_eq!(root.get("port").unwrap().as_i128(), Some(8080));
+    assert_eq!(root.get("huge").unwrap().as_i128(), Some(i128::MAX));
+    assert_eq!(root.get("ratio").unwrap().as_f64(), Some(0.75));
+    assert_eq!(root.get("enabled").unwrap().as_bool(), Some(true));
+    assert_eq!(root.get("timeout").unwrap().get_type(), "Duration");
+    assert!(root.get("missing").is_none());
+    assert!(root.get("port").unwrap().get("x").is_none());
+
+    let replicas = root.get("replicas").unwrap();
+    assert_eq!(replicas.len(), 2);
+    assert_eq!(replicas.get_index(1).unwrap().get("zone").unwrap().as_str(), Some("b"));
+    assert!(replicas.get_index(2).is_none());
+
+    let path: Path = "$.replicas[0].zone".parse().unwrap();
+    assert_eq!(root.get_path(&path).unwrap().as_str(), Some("a"));
+}
+
+#[test]
+fn test_iteration_is_ordered() {
+    let document = CompactDocument::from_value(&son!({ b: 2 a: 1 c: [x y] })).unwrap();
+    let keys: Vec<&str> = document.root().entries_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["a", "b", "c"]);
+
+    let items: Vec<&str> = document
+        .root()
+        .get("c")
+        .unwrap()
+        .iter()
+        .map(|item| item.as_str().unwrap())
+        .collect();
+    assert_eq!(items, ["x", "y"]);
+    assert_eq!(document.root().get("a").unwrap().iter().count(), 0);
+}
+
+#[test]
+fn test_round_trip_to_value() {
+    let input = records(20);
+    let value: Value = from_str(&input).unwrap();
+    assert_eq!(CompactDocument::parse(&input).unwrap().to_value(), value);
+    assert_eq!(CompactDocument::from_value(&value).unwrap().to_value(), value);
+
+    let mixed = son!({
+        empty: "" short: "fourteen bytes" longer: "fifteen bytes!!" unicode: "héllo wörld, ünïcode"
+        nothing: null bytes: #(son::Bytes::new(vec![1, 2, 3])) wait: #(Duration::from_millis(1500))
+        nested: [[] {} [[1]]]
+    });
+    assert_eq!(CompactDocument::from_value(&mixed).unwrap().to_value(), mixed);
+
+    let input = r#"{ "quoted key": "tab\tescaped" n: [-1 --2 -9223372036854775808 -170141183460469231731687303715884105727]
+        f: -1.5 s: Status.Active p: accounts::Status::Closed b: -true }"#;
+    let value: Value = from_str(input).unwrap();
+    assert_eq!(CompactDocument::parse(input).unwrap().to_value(), value);
+}
+
+#[test]
+fn test_compact_document_is_smaller() {
+    let input = records(1000);
+    let value: Value = from_str(&input).unwrap();
+    let document = CompactDocument::parse(&input).unwrap();
+
+    let value_size = value.heap_size();
+    let compact_size = document.heap_size();
+    assert!(
+        compact_size * 3 < value_size,
+        "compact: {} bytes, value: {} bytes",
+        compact_size,
+        value_size
+    );
+}
+
+#[test]
+fn test_value_heap_size() {
+    assert_eq!(Value::Null.heap_size(), 0);
+    assert_eq!(Value::Integer(5).heap_size(), 0);
+    assert_eq!(Value::String(String::with_capacity(32)).heap_size(), 32);
+
+    let array = Value::Array(Vec::with_capacity(4));
+    assert_eq!(array.heap_size(), 4 * size_of::<Value>());
+
+    let nested = Value::Array(vec![Value::String("abc".to_string())]);
+    assert_eq!(nested.heap_size(), size_of::<Value>() + 3);
+    assert!(son!({ key: "value" }).heap_size() > "key".len() + "value".len());
+}
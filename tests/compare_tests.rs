@@ -0,0 +1,135 @@
+use son::{BigInt, Number, Value, son};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+fn hash_of(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    return hasher.finish();
+}
+
+fn number(literal: &str) -> Value {
+    return Value::Number(Number::new(literal).unwrap());
+}
+
+#[test]
+fn test_order_across_kinds() {
+    let mut values = vec![
+        son!({ a: 1 }),
+        son!([1 2]),
+        Value::Enum("Active".into()),
+        Value::String("text".to_string()),
+        Value::Char('c'),
+        Value::Integer(3),
+        Value::Bool(true),
+        Value::Null,
+        Value::Bytes(vec![1]),
+    ];
+    values.sort();
+    let kinds: Vec<String> = values.iter().map(|v| v.get_type()).collect();
+    assert_eq!(
+        kinds,
+        [
+            "Null", "Bool", "Integer", "Char", "String", "Enum", "Bytes", "Array", "Object"
+        ]
+    );
+}
+
+#[test]
+fn test_numbers_order_by_value() {
+    let big = Value::from("100000000000000000000000000000000000000000".parse::<BigInt>().unwrap());
+    let mut values = vec![
+        Value::Float(f64::NAN),
+        big.clone(),
+        Value::Float(2.5),
+        number("2.49"),
+        Value::Integer(-3),
+        Value::Float(f64::INFINITY),
+        Value::Float(f64::NEG_INFINITY),
+        Value::Float(1e300),
+        Value::Integer(i128::MAX),
+    ];
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            Value::Float(f64::NEG_INFINITY),
+            Value::Integer(-3),
+            number("2.49"),
+            Value::Float(2.5),
+            Value::Integer(i128::MAX),
+            big,
+            Value::Float(1e300),
+            Value::Float(f64::INFINITY),
+            Value::Float(f64::NAN),
+        ]
+    );
+
+    // 0.1 as a float is slightly more than the decimal 0.1.
+    assert_eq!(number("0.1").cmp(&Value::Float(0.1)), Ordering::Less);
+    assert_eq!(
+        number("0.1000000000000000055511151231257827021181583404541015625").cmp(&Value::Float(0.1)),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn test_numerically_equal_values_are_distinct() {
+    let equal_values = [Value::Integer(1), Value::Float(1.0), number("1"), number("1.0")];
+    for (i, a) in equal_values.iter().enumerate() {
+        for (j, b) in equal_values.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{:?} vs {:?}", a, b);
+            assert_eq!(a == b, i == j);
+        }
+    }
+
+    let set: BTreeSet<Value> = equal_values.iter().cloned().collect();
+    assert_eq!(set.len(), 4);
+}
+
+#[test]
+fn test_canonical_floats() {
+    assert_eq!(Value::Float(f64::NAN), Value::Float(-f64::NAN));
+    assert_eq!(hash_of(&Value::Float(f64::NAN)), hash_of(&Value::Float(-f64::NAN)));
+    assert_eq!(Value::Float(0.0), Value::Float(-0.0));
+    assert_eq!(hash_of(&Value::Float(0.0)), hash_of(&Value::Float(-0.0)));
+    assert_eq!(Value::Float(-0.0).cmp(&Value::Float(0.0)), Ordering::Equal);
+    assert_eq!(
+        Value::Float(f64::NAN).cmp(&Value::Float(f64::INFINITY)),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn test_dedupe_and_group_documents() {
+    let mut first = HashMap::new();
+    first.insert("name".to_string(), Value::String("a".to_string()));
+    first.insert("tags".to_string(), son!([x y]));
+    let mut second = HashMap::new();
+    second.insert("tags".to_string(), son!([x y]));
+    second.insert("name".to_string(), Value::String("a".to_string()));
+    let (first, second) = (Value::Object(first), Value::Object(second));
+    assert_eq!(hash_of(&first), hash_of(&second));
+
+    let documents = vec![
+        first.clone(),
+        son!({ name: "b" }),
+        second,
+        Value::Float(f64::NAN),
+        Value::Float(f64::NAN),
+    ];
+    let unique: HashSet<Value> = documents.iter().cloned().collect();
+    assert_eq!(unique.len(), 3);
+
+    let mut groups: BTreeMap<Value, usize> = BTreeMap::new();
+    for document in documents {
+        *groups.entry(document).or_default() += 1;
+    }
+    assert_eq!(groups[&first], 2);
+    assert_eq!(groups.keys().next(), Some(&Value::Float(f64::NAN)));
+    assert_eq!(son!({ name: "a" }).cmp(&son!({ name: "b" })), Ordering::Less);
+    assert_eq!(son!({ a: 2 }).cmp(&son!({ a: 1 b: 1 })), Ordering::Greater);
+}
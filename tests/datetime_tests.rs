@@ -0,0 +1,179 @@
+use son::{Date, DateTime, DeserializationError, Deserialize, FromSon, Offset, Serialize, Time, ToSon, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Rollout {
+    name: String,
+    cut_over: DateTime,
+    created: SystemTime,
+}
+
+fn field(value: &Value, key: &str) -> Value {
+    return value.get_path(&son::Path::root().key(key)).unwrap().clone();
+}
+
+fn datetime(s: &str) -> DateTime {
+    return s.parse::<DateTime>().unwrap();
+}
+
+#[test]
+fn test_parse_datetime_literals() {
+    let value = son::from_str::<Value>(
+        r#"{
+            date: 2024-02-29
+            time: 08:30:00
+            local: 2024-01-15T08:30:00.250
+            utc: 2024-01-15T08:30:00Z
+            offset: 2024-01-15t08:30:00.000000001-05:30
+            list: [2024-01-01 2025-01-01]
+        }"#,
+    )
+    .unwrap();
+
+    let date = Date::new(2024, 2, 29).unwrap();
+    assert_eq!(field(&value, "date"), Value::DateTime(DateTime::Date(date)));
+    assert_eq!(
+        field(&value, "time"),
+        Value::DateTime(DateTime::Time(Time::new(8, 30, 0, 0).unwrap()))
+    );
+    assert_eq!(
+        field(&value, "local"),
+        Value::DateTime(datetime("2024-01-15T08:30:00.25"))
+    );
+    assert_eq!(
+        field(&value, "utc"),
+        Value::DateTime(datetime("2024-01-15T08:30:00+00:00"))
+    );
+
+    let Value::DateTime(offset) = field(&value, "offset") else {
+        panic!("expected a date time");
+    };
+    assert_eq!(offset.offset(), Offset::new(-330));
+    assert_eq!(offset.time().unwrap().nanosecond(), 1);
+    assert_eq!(field(&value, "list").get_type(), "Array");
+}
+
+#[test]
+fn test_calendar_validation() {
+    let invalid = [
+        "2023-02-29",
+        "2100-02-29",
+        "2024-04-31",
+        "2024-13-01",
+        "2024-00-10",
+        "2024-01-00",
+        "24:00:00",
+        "12:60:00",
+        "12:00:61",
+        "2024-01-15T08:30:00+24:00",
+        "2024-01-15T08:30",
+        "2024-01-15T08:30:00.1234567891",
+        "2024-1-15",
+    ];
+    for literal in invalid {
+        assert!(literal.parse::<DateTime>().is_err(), "{} should be invalid", literal);
+        let result = son::from_str::<Value>(&format!("{{ at: {} }}", literal));
+        assert!(result.is_err(), "{} should not parse", literal);
+    }
+
+    assert!("2000-02-29".parse::<DateTime>().is_ok());
+    assert!("23:59:60".parse::<DateTime>().is_ok());
+    assert!(son::from_str::<Value>("{ a: 12 b: -2024 c: 2024.5 }").is_ok());
+}
+
+#[test]
+fn test_datetimes_print_and_round_trip() {
+    let cases = [
+        "2024-01-15",
+        "08:30:00",
+        "08:30:00.5",
+        "2024-01-15T08:30:00.123456789",
+        "2024-01-15T08:30:00Z",
+        "2024-01-15T08:30:00+02:00",
+        "0001-01-01T00:00:00-12:45",
+    ];
+    for case in cases {
+        assert_eq!(datetime(case).to_string(), case);
+        let printed = son::to_string(&Value::Array(vec![Value::DateTime(datetime(case))]));
+        let reparsed = son::from_str::<Value>(&printed).unwrap();
+        assert_eq!(reparsed, Value::Array(vec![Value::DateTime(datetime(case))]));
+    }
+    assert_eq!(
+        datetime("2024-01-15T08:30:00+00:00").to_string(),
+        "2024-01-15T08:30:00Z"
+    );
+}
+
+#[test]
+fn test_system_time_conversions() {
+    assert_eq!(
+        SystemTime::from_son(Value::DateTime(datetime("1970-01-01T00:00:00Z"))).unwrap(),
+        UNIX_EPOCH
+    );
+    assert_eq!(
+        SystemTime::from_son(Value::DateTime(datetime("2024-01-15T10:30:00.5+02:00"))).unwrap(),
+        UNIX_EPOCH + Duration::new(1_705_307_400, 500_000_000)
+    );
+    assert_eq!(
+        SystemTime::from_son(Value::DateTime(datetime("1969-12-31T23:59:59.75Z"))).unwrap(),
+        UNIX_EPOCH - Duration::from_millis(250)
+    );
+
+    let instant = UNIX_EPOCH + Duration::new(4_102_444_800, 42);
+    assert_eq!(
+        instant.to_son(),
+        Value::DateTime(datetime("2100-01-01T00:00:00.000000042Z"))
+    );
+    assert_eq!(SystemTime::from_son(instant.to_son()).unwrap(), instant);
+
+    let before_epoch = UNIX_EPOCH - Duration::new(86_400 * 365, 1);
+    assert_eq!(
+        before_epoch.to_son(),
+        Value::DateTime(datetime("1968-12-31T23:59:59.999999999Z"))
+    );
+    assert_eq!(SystemTime::from_son(before_epoch.to_son()).unwrap(), before_epoch);
+
+    assert!(SystemTime::from_son(Value::DateTime(datetime("2024-01-15T08:30:00"))).is_err());
+    assert!(SystemTime::from_son(Value::DateTime(datetime("2024-01-15"))).is_err());
+    assert!(SystemTime::from_son(Value::String("2024-01-15T08:30:00Z".to_string())).is_err());
+}
+
+#[test]
+fn test_system_time_range() {
+    let first = datetime("0000-01-01T00:00:00Z");
+    let last = datetime("9999-12-31T23:59:59.999999999Z");
+    for (datetime, outside) in [
+        (first, first.to_system_time().unwrap() - Duration::from_nanos(1)),
+        (last, last.to_system_time().unwrap() + Duration::from_nanos(1)),
+    ] {
+        let instant = datetime.to_system_time().unwrap();
+        assert_eq!(DateTime::from_system_time(instant), Some(datetime));
+        assert_eq!(
+            son::from_str::<Value>(&format!("[{}]", datetime)).unwrap(),
+            Value::Array(vec![Value::DateTime(datetime)])
+        );
+
+        // One nanosecond further can't be written with a four digit year.
+        assert_eq!(DateTime::from_system_time(outside), None);
+        assert_eq!(outside.to_son(), Value::Null);
+    }
+    assert_eq!(Date::new(10000, 1, 1), None);
+    assert_eq!(Date::new(-1, 12, 31), None);
+}
+
+#[test]
+fn test_serialize_and_deserialize_datetimes() {
+    let rollout = Rollout {
+        name: "v2".to_string(),
+        cut_over: datetime("2024-06-01"),
+        created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+    };
+
+    let printed = son::to_string(&rollout);
+    assert!(printed.contains("cut_over:2024-06-01"));
+    assert!(printed.contains("created:2023-11-14T22:13:20Z"));
+    assert_eq!(son::from_str::<Rollout>(&printed).unwrap(), rollout);
+
+    let result = son::from_str::<Rollout>(r#"{ name: "v2" cut_over: 2024-06-01 created: 2024-06-01 }"#);
+    assert!(result.is_err());
+}
@@ -0,0 +1,105 @@
+use son::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value, from_str, to_son};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Listing {
+    title: String,
+    subtitle: Option<String>,
+    rating: Option<u8>,
+}
+
+#[test]
+fn test_missing_option_fields_are_none() {
+    let listing: Listing = from_str(r#"{ title: "Dune" }"#).unwrap();
+    assert_eq!(
+        listing,
+        Listing {
+            title: "Dune".to_string(),
+            subtitle: None,
+            rating: None,
+        }
+    );
+
+    let listing: Listing = from_str(r#"{ title: "Dune" subtitle: null rating: 5 }"#).unwrap();
+    assert_eq!(listing.rating, Some(5));
+    assert!(from_str::<Listing>(r#"{ subtitle: "Missing title" }"#).is_err());
+}
+
+#[test]
+fn test_option_values_serialize() {
+    assert_eq!(to_son(&Some(3)), Value::Integer(3));
+    assert_eq!(to_son(&None::<String>), Value::Null);
+
+    let listing = Listing {
+        title: "Dune".to_string(),
+        subtitle: None,
+        rating: Some(4),
+    };
+    let Value::Object(map) = to_son(&listing) else {
+        panic!("Expected an object");
+    };
+    assert_eq!(map["subtitle"], Value::Null);
+    assert_eq!(map["rating"], Value::Integer(4));
+    assert_eq!(from_str::<Listing>(&son::to_string(&listing)).unwrap(), listing);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Measurement {
+    ratio: f64,
+    scale: f32,
+}
+
+#[test]
+fn test_integers_are_accepted_as_floats() {
+    let measurement: Measurement = from_str("{ ratio: 2 scale: -3 }").unwrap();
+    assert_eq!(
+        measurement,
+        Measurement {
+            ratio: 2.0,
+            scale: -3.0
+        }
+    );
+
+    let measurement: Measurement = from_str("{ ratio: 0.5 scale: 1.5 }").unwrap();
+    assert_eq!(measurement, Measurement { ratio: 0.5, scale: 1.5 });
+    assert!(from_str::<Measurement>(r#"{ ratio: "2" scale: 1 }"#).is_err());
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Token {
+    r#type: String,
+    r#match: bool,
+}
+
+#[test]
+fn test_raw_identifiers_use_their_plain_name() {
+    let token: Token = from_str(r#"{ type: "keyword" match: true }"#).unwrap();
+    assert_eq!(
+        token,
+        Token {
+            r#type: "keyword".to_string(),
+            r#match: true,
+        }
+    );
+
+    let Value::Object(map) = to_son(&token) else {
+        panic!("Expected an object");
+    };
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["match", "type"]);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Envelope {
+    kind: String,
+    attributes: HashMap<String, Value>,
+}
+
+#[test]
+fn test_generic_field_types() {
+    let envelope: Envelope = from_str(r#"{ kind: "event" attributes: { retries: 3 source: "api" } }"#).unwrap();
+    assert_eq!(envelope.kind, "event");
+    assert_eq!(envelope.attributes.len(), 2);
+    assert_eq!(envelope.attributes["retries"], Value::Integer(3));
+}
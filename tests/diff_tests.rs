@@ -0,0 +1,135 @@
+use son::{Operation, Patch, PatchError, Value};
+
+const BEFORE: &str = r#"{
+    name: "api"
+    server: { host: "localhost" port: 8080 }
+    features: ["auth" "cache" "metrics"]
+    legacy_timeout: 30
+}"#;
+
+const AFTER: &str = r#"{
+    name: "api"
+    server: { host: "0.0.0.0" port: 8080 tls: true }
+    features: ["auth" "search"]
+    timeout: 30
+}"#;
+
+fn parse(source: &str) -> Value {
+    return son::from_str::<Value>(source).unwrap();
+}
+
+#[test]
+fn test_diff_operations() {
+    let patch = son::diff(&parse(BEFORE), &parse(AFTER));
+
+    assert_eq!(
+        patch.to_string(),
+        [
+            "~ $.features[1]: \"cache\" -> \"search\"",
+            "- $.features[2]: \"metrics\"",
+            "~ $.server.host: \"localhost\" -> \"0.0.0.0\"",
+            "+ $.server.tls: true",
+            "> $.legacy_timeout -> $.timeout",
+            "",
+        ]
+        .join("\n")
+    );
+    assert!(son::diff(&parse(BEFORE), &parse(BEFORE)).is_empty());
+}
+
+#[test]
+fn test_apply_patch() {
+    let before = parse(BEFORE);
+    let after = parse(AFTER);
+
+    let mut value = before.clone();
+    value.apply_patch(&before.diff(&after)).unwrap();
+    assert_eq!(value, after);
+
+    let mut value = after.clone();
+    son::apply_patch(&mut value, &after.diff(&before)).unwrap();
+    assert_eq!(value, before);
+
+    let grown = parse(r#"{ list: [1 2 3 4 5] }"#);
+    let shrunk = parse(r#"{ list: [1] }"#);
+    let mut value = grown.clone();
+    value.apply_patch(&grown.diff(&shrunk)).unwrap();
+    assert_eq!(value, shrunk);
+    value.apply_patch(&shrunk.diff(&grown)).unwrap();
+    assert_eq!(value, grown);
+}
+
+#[test]
+fn test_apply_patch_preconditions() {
+    let before = parse(BEFORE);
+    let patch = before.diff(&parse(AFTER));
+
+    let mut modified = before.clone();
+    modified
+        .apply_patch(&Patch::new(vec![Operation::Replace {
+            path: "$.server.host".parse().unwrap(),
+            old: Value::String("localhost".to_string()),
+            value: Value::String("example.com".to_string()),
+        }]))
+        .unwrap();
+
+    let unchanged = modified.clone();
+    let result = modified.apply_patch(&patch);
+    assert_eq!(
+        result,
+        Err(PatchError::PreconditionFailed {
+            path: "$.server.host".to_string(),
+            expected: "\"localhost\"".to_string(),
+            found: "\"example.com\"".to_string(),
+        })
+    );
+    // A failed patch leaves the value untouched.
+    assert_eq!(modified, unchanged);
+
+    // Moves check the value they take away as well.
+    let mut modified = before.clone();
+    *modified.get_path_mut(&"$.legacy_timeout".parse().unwrap()).unwrap() = Value::Integer(45);
+    assert_eq!(
+        modified.apply_patch(&patch),
+        Err(PatchError::PreconditionFailed {
+            path: "$.legacy_timeout".to_string(),
+            expected: "30".to_string(),
+            found: "45".to_string(),
+        })
+    );
+
+    let mut value = parse(BEFORE);
+    let add_existing = Patch::new(vec![Operation::Add {
+        path: "$.name".parse().unwrap(),
+        value: Value::Null,
+    }]);
+    assert!(matches!(
+        value.apply_patch(&add_existing),
+        Err(PatchError::PathExists { .. })
+    ));
+
+    let remove_missing = Patch::new(vec![Operation::Remove {
+        path: "$.missing.key".parse().unwrap(),
+        old: Value::Null,
+    }]);
+    assert!(matches!(
+        value.apply_patch(&remove_missing),
+        Err(PatchError::PathNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_patch_son_round_trip() {
+    let patch = son::diff(&parse(BEFORE), &parse(AFTER));
+
+    let son_string = son::to_string(&patch);
+    let deserialized: Patch = son::from_str(&son_string).unwrap();
+    assert_eq!(deserialized, patch);
+
+    let mut value = parse(BEFORE);
+    value.apply_patch(&deserialized).unwrap();
+    assert_eq!(value, parse(AFTER));
+
+    let invalid = son::from_str::<Patch>(r#"[ { op: Rename path: "$.a" } ]"#);
+    assert!(invalid.is_err());
+}
@@ -0,0 +1,123 @@
+use son::{
+    CompactDocument, DeserializationError, Deserialize, EnumVariant, Error, FromSon, Path, PrinterOptions, Serialize,
+    ToSon, Value, from_str, from_str_borrowed, son, to_son, to_string_with,
+};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Account {
+    status: Status,
+}
+
+fn variant(value: &Value, key: &str) -> EnumVariant {
+    let Some(Value::Enum(variant)) = value.get_path(&Path::root().key(key)) else {
+        panic!("Expected an enum at {}", key);
+    };
+    return variant.clone();
+}
+
+#[test]
+fn test_parse_qualified_variants() {
+    let value: Value = from_str("{ a: Status.Active b: accounts::Status::Inactive c: Active }").unwrap();
+
+    let a = variant(&value, "a");
+    assert_eq!(a.qualifier(), Some("Status"));
+    assert_eq!(a.name(), "Active");
+    assert_eq!(a.to_string(), "Status.Active");
+
+    let b = variant(&value, "b");
+    assert_eq!(b.qualifier(), Some("accounts::Status"));
+    assert_eq!(b.type_name(), Some("Status"));
+    assert_eq!(b.to_string(), "accounts::Status::Inactive");
+
+    // Variants compare by type name and name, ignoring the module path, with unqualified variants first.
+    let c = variant(&value, "c");
+    assert_eq!(c.qualifier(), None);
+    assert_ne!(a, c);
+    assert!(c < EnumVariant::qualified("Mode", "Active"));
+    assert_eq!(a, EnumVariant::qualified("accounts::Status", "Active"));
+    assert_ne!(a, EnumVariant::qualified("Mode", "Active"));
+    assert!(EnumVariant::qualified("Mode", "Active") < a);
+    assert_eq!(Value::Enum(a).to_canonical_string(), "Status.Active");
+    assert_eq!(Value::Enum(b).to_canonical_string(), "Status.Inactive");
+
+    let variants: BTreeSet<Value> = ["{ s: Mode.Active }", "{ s: Status.Active }", "{ s: Active }"]
+        .iter()
+        .map(|source| from_str::<Value>(source).unwrap())
+        .collect();
+    assert_eq!(variants.len(), 3);
+    let status = son!({ s: Status.Active });
+    assert_eq!(son::diff(&status, &son!({ s: Mode.Active })).operations.len(), 1);
+    assert_eq!(son::diff(&status, &son!({ s: Active })).operations.len(), 1);
+    assert_eq!(son::diff(&status, &son!({ s: a::Status::Active })).operations.len(), 0);
+}
+
+#[test]
+fn test_derived_from_son_checks_qualifier() {
+    let account: Account = from_str("{ status: Status.Inactive }").unwrap();
+    assert_eq!(account.status, Status::Inactive);
+    let account: Account = from_str("{ status: accounts::Status::Active }").unwrap();
+    assert_eq!(account.status, Status::Active);
+    let account: Account = from_str("{ status: Active }").unwrap();
+    assert_eq!(account.status, Status::Active);
+
+    let Err(Error::DeserializationError(DeserializationError::UnknownVariant { variant, enum_name })) =
+        from_str::<Account>("{ status: Mode.Active }")
+    else {
+        panic!("Expected an unknown variant error");
+    };
+    assert_eq!(variant, "Mode.Active");
+    assert_eq!(enum_name, "Status");
+}
+
+#[test]
+fn test_printer_qualifies_on_request() {
+    let value = to_son(&Account { status: Status::Active });
+    assert_eq!(variant(&value, "status").qualifier(), Some("Status"));
+
+    assert_eq!(son::to_string_pretty(&value, "    "), "{ status: Active }\n");
+    assert_eq!(son::to_string(&value), "{status:Active}");
+
+    let qualified = PrinterOptions::new().qualify_enums(true);
+    let printed = to_string_with(&value, &qualified);
+    assert_eq!(printed, "{ status: Status.Active }\n");
+    assert_eq!(from_str::<Account>(&printed).unwrap().status, Status::Active);
+    assert_eq!(from_str::<Value>(&printed).unwrap(), value);
+
+    let unqualified = son!({ status: Active });
+    assert_eq!(to_string_with(&unqualified, &qualified), "{ status: Active }\n");
+}
+
+#[test]
+fn test_invalid_qualified_syntax() {
+    assert!(from_str::<Value>("{ Status.Active: 1 }").is_err());
+    assert!(from_str::<Value>("{ a: Status. }").is_err());
+    assert!(from_str::<Value>("{ a: Status::1 }").is_err());
+    assert!(from_str::<Value>("{ a: .Active }").is_err());
+}
+
+#[test]
+fn test_other_representations_keep_qualifier() {
+    let input = "{ a: Status.Active b: accounts::Status::Inactive c: Active }";
+    let value = son!({ a: Status.Active b: accounts::Status::Inactive c: Active });
+    assert_eq!(value, from_str::<Value>(input).unwrap());
+    assert_eq!(variant(&value, "b").qualifier(), Some("accounts::Status"));
+
+    let borrowed = from_str_borrowed(input).unwrap();
+    assert_eq!(borrowed.get("a").unwrap().as_str(), Some("Active"));
+    assert_eq!(variant(&borrowed.into_owned(), "a").qualifier(), Some("Status"));
+
+    let compact = CompactDocument::parse(input).unwrap();
+    assert_eq!(compact.root().get("a").unwrap().get_type(), "Enum");
+    assert_eq!(
+        variant(&compact.to_value(), "b").to_string(),
+        "accounts::Status::Inactive"
+    );
+    assert_eq!(variant(&compact.to_value(), "c").qualifier(), None);
+}
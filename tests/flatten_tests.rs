@@ -0,0 +1,165 @@
+use son::{FlattenOptions, Value, son};
+use std::collections::HashMap;
+
+fn settings() -> Value {
+    return son! {
+        server: { host: "localhost" ports: [8080 8443] tls: { enabled: true } }
+        features: []
+        labels: {}
+        name: "api"
+    };
+}
+
+fn pair(key: &str, value: Value) -> (String, Value) {
+    return (key.to_string(), value);
+}
+
+#[test]
+fn test_flatten_with_dotted_keys() {
+    assert_eq!(
+        settings().flatten(),
+        vec![
+            pair("features", Value::Array(Vec::new())),
+            pair("labels", Value::Object(HashMap::new())),
+            pair("name", Value::String("api".to_string())),
+            pair("server.host", Value::String("localhost".to_string())),
+            pair("server.ports[0]", Value::Integer(8080)),
+            pair("server.ports[1]", Value::Integer(8443)),
+            pair("server.tls.enabled", Value::Bool(true)),
+        ]
+    );
+    assert_eq!(
+        son!([[1] 2]).flatten(),
+        vec![pair("[0][0]", Value::Integer(1)), pair("[1]", Value::Integer(2))]
+    );
+    assert_eq!(Value::Integer(1).flatten(), vec![pair("", Value::Integer(1))]);
+}
+
+#[test]
+fn test_flatten_with_custom_separator() {
+    let options = FlattenOptions::new().separator("__").brackets(false);
+    let keys: Vec<String> = settings()
+        .flatten_with(options.clone())
+        .unwrap()
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "features",
+            "labels",
+            "name",
+            "server__host",
+            "server__ports__0",
+            "server__ports__1",
+            "server__tls__enabled"
+        ]
+    );
+
+    let rebuilt = Value::unflatten_with(settings().flatten_with(options.clone()).unwrap(), options).unwrap();
+    assert_eq!(rebuilt, settings());
+}
+
+#[test]
+fn test_escaping_keys_with_separators() {
+    let mut inner = HashMap::new();
+    inner.insert("a.b".to_string(), Value::Integer(1));
+    inner.insert("c[0]".to_string(), Value::Integer(2));
+    inner.insert("back\\slash".to_string(), Value::Integer(3));
+    inner.insert("7".to_string(), Value::Integer(4));
+    inner.insert(String::new(), Value::Integer(5));
+    let mut root = HashMap::new();
+    root.insert("x".to_string(), Value::Object(inner));
+    let value = Value::Object(root);
+
+    let flat = value.flatten();
+    let keys: Vec<&str> = flat.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["x.", "x.7", "x.a\\.b", "x.back\\\\slash", "x.c\\[0]"]);
+    assert_eq!(Value::unflatten(flat).unwrap(), value);
+
+    let options = FlattenOptions::new().separator("__").brackets(false);
+    let flat = value.flatten_with(options.clone()).unwrap();
+    let keys: Vec<&str> = flat.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["x__", "x__\\7", "x__a.b", "x__back\\\\slash", "x__c[0]"]);
+    assert_eq!(Value::unflatten_with(flat, options).unwrap(), value);
+
+    let mut tricky = HashMap::new();
+    tricky.insert("a__".to_string(), son!({ b: 1 }));
+    let tricky = Value::Object(tricky);
+    let options = FlattenOptions::new().separator("__");
+    let flat = tricky.flatten_with(options.clone()).unwrap();
+    assert_eq!(flat, vec![pair("a\\_\\___b", Value::Integer(1))]);
+    assert_eq!(Value::unflatten_with(flat, options).unwrap(), tricky);
+
+    // An empty key at the root is marked with a leading separator, since an empty flattened key is a leaf root.
+    let empty_keys = son!({ "": 1 "b": { "": [2] } });
+    let mut nested = HashMap::new();
+    nested.insert(String::new(), son!({ "": [3] c: 4 }));
+    for value in [empty_keys, Value::Object(nested)] {
+        for options in [
+            FlattenOptions::new(),
+            FlattenOptions::new().separator("__").brackets(false),
+        ] {
+            let flat = value.flatten_with(options.clone()).unwrap();
+            assert_eq!(Value::unflatten_with(flat, options).unwrap(), value);
+        }
+    }
+    let keys: Vec<String> = son!({ "": [1] }).flatten().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, [".[0]"]);
+    assert_eq!(
+        Value::unflatten(vec![pair(".", Value::Integer(1))]).unwrap(),
+        son!({ "": 1 })
+    );
+}
+
+#[test]
+fn test_unflatten_rebuilds_documents() {
+    let pairs = vec![
+        pair("server.ports[1]", Value::Integer(8443)),
+        pair("server.ports[0]", Value::Integer(8080)),
+        pair("server.host", Value::String("localhost".to_string())),
+        pair("matrix[0][1]", Value::Integer(2)),
+        pair("matrix[0][0]", Value::Integer(1)),
+    ];
+    assert_eq!(
+        Value::unflatten(pairs).unwrap(),
+        son!({ server: { host: "localhost" ports: [8080 8443] } matrix: [[1 2]] })
+    );
+    assert_eq!(Value::unflatten(Vec::new()).unwrap(), son!({}));
+    assert_eq!(Value::unflatten(vec![pair("", Value::Null)]).unwrap(), Value::Null);
+    assert_eq!(Value::unflatten(settings().flatten()).unwrap(), settings());
+}
+
+#[test]
+fn test_unflatten_errors() {
+    let error = Value::unflatten(vec![pair("a", Value::Integer(1)), pair("a.b", Value::Integer(2))]).unwrap_err();
+    assert_eq!(error.key, "a.b");
+    assert_eq!(
+        error.to_string(),
+        "Invalid flattened key 'a.b': conflicts with another key"
+    );
+
+    assert!(Value::unflatten(vec![pair("a", Value::Integer(1)), pair("a", Value::Integer(2))]).is_err());
+    assert!(Value::unflatten(vec![pair("a.b", Value::Integer(1)), pair("a[0]", Value::Integer(2))]).is_err());
+
+    let error = Value::unflatten(vec![
+        pair("list[0]", Value::Integer(1)),
+        pair("list[2]", Value::Integer(3)),
+    ])
+    .unwrap_err();
+    assert_eq!(error.key, "list[1]");
+
+    let options = FlattenOptions::new().separator("");
+    let error = settings().flatten_with(options.clone()).unwrap_err();
+    assert_eq!(error.message, "the separator can't be empty");
+    assert!(Value::unflatten_with(vec![pair("a", Value::Null)], options).is_err());
+
+    for key in ["a[", "a[x]", "a[-1]", "a[0]b", "a\\"] {
+        assert!(
+            Value::unflatten(vec![pair(key, Value::Null)]).is_err(),
+            "{} should be invalid",
+            key
+        );
+    }
+}
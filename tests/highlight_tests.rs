@@ -0,0 +1,132 @@
+use son::{
+    ColorChoice, PrinterOptions, Theme, from_str, highlight::highlight_source, son, to_string_highlighted,
+    to_string_with, to_writer_highlighted,
+};
+use std::{fs::File, io::IsTerminal};
+
+// Replaces each escape code with the name of the theme entry it starts, so expectations stay readable.
+fn tags(text: &str) -> String {
+    let theme = Theme::new();
+    let names = [
+        (theme.key, "<key>"),
+        (theme.string, "<string>"),
+        (theme.number, "<number>"),
+        (theme.keyword, "<keyword>"),
+        (theme.enum_variant, "<enum>"),
+        (theme.punctuation, "<p>"),
+        (theme.comment, "<comment>"),
+        (theme.error, "<error>"),
+    ];
+    let mut tagged = text.replace("\x1b[0m", "</>");
+    for (style, name) in names {
+        tagged = tagged.replace(&format!("\x1b[{}m", style), name);
+    }
+    return tagged;
+}
+
+fn strip(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start + rest[start..].find('m').unwrap() + 1..];
+    }
+    stripped.push_str(rest);
+    return stripped;
+}
+
+#[test]
+fn test_value_output_is_coloured() {
+    let value = son!({ name: "api" port: -8080 mode: Mode.Fast enabled: true });
+    let options = PrinterOptions::compact().sort_keys(true).space_after_colon(true);
+    let highlighted = to_string_highlighted(&value, &options, &Theme::new());
+    assert_eq!(
+        tags(&highlighted),
+        "<p>{</><key>enabled</><p>:</> <keyword>true</><p>,</><key>mode</><p>:</> <enum>Fast</><p>,</>\
+         <key>name</><p>:</> <string>\"api\"</><p>,</><key>port</><p>:</> <number>-</><number>8080</><p>}</>"
+    );
+    assert_eq!(strip(&highlighted), to_string_with(&value, &options));
+}
+
+#[test]
+fn test_source_keeps_comments_and_layout() {
+    let source = "{\n    // Where to listen.\n    \"max connections\": 10 /* per worker */\n    limit: 64KiB\n}\n";
+    let highlighted = highlight_source(source, &Theme::new());
+    assert_eq!(strip(&highlighted), source);
+    assert_eq!(
+        tags(&highlighted),
+        "<p>{</>\n    <comment>// Where to listen.</>\n    <key>\"max connections\"</><p>:</> <number>10</> \
+         <comment>/* per worker */</>\n    <key>limit</><p>:</> <number>64KiB</>\n<p>}</>\n"
+    );
+
+    // Keys may be followed by an object or array without a colon, while values inside arrays are not keys.
+    assert_eq!(
+        tags(&highlight_source(
+            "{ server { port: 1 } tags [Fast [x]] }",
+            &Theme::new()
+        )),
+        "<p>{</> <key>server</> <p>{</> <key>port</><p>:</> <number>1</> <p>}</> <key>tags</> <p>[</><enum>Fast</> \
+         <p>[</><enum>x</><p>]</><p>]</> <p>}</>"
+    );
+}
+
+#[test]
+fn test_errors_are_marked() {
+    let source = "{ ok: 'x' bad: \"unterminated }";
+    let highlighted = highlight_source(source, &Theme::new());
+    assert_eq!(strip(&highlighted), source);
+    assert!(
+        tags(&highlighted).ends_with("<error>\"unterminated }</>"),
+        "{}",
+        tags(&highlighted)
+    );
+    assert!(from_str::<son::Value>(source).is_err());
+
+    assert_eq!(
+        tags(&highlight_source("[1 @ 2]", &Theme::new())),
+        "<p>[</><number>1</> <error>@</> <number>2</><p>]</>"
+    );
+}
+
+#[test]
+fn test_custom_theme() {
+    let theme = Theme::new().key("1;34").punctuation("").string("");
+    let highlighted = highlight_source("{a:\"b\"}", &theme);
+    assert_eq!(highlighted, "{\x1b[1;34ma\x1b[0m:\"b\"}");
+}
+
+#[test]
+fn test_color_choice() {
+    let value = son!([1 2]);
+    let options = PrinterOptions::compact();
+    let path = std::env::temp_dir().join(format!("son-highlight-{}.son", std::process::id()));
+
+    // A regular file isn't a terminal, so `Auto` writes plain text.
+    let file = File::create(&path).unwrap();
+    assert!(!file.is_terminal());
+    assert!(!ColorChoice::Auto.should_color(&file));
+    to_writer_highlighted(file, &value, &options, &Theme::new(), ColorChoice::Auto).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "[1,2]");
+
+    let file = File::create(&path).unwrap();
+    to_writer_highlighted(file, &value, &options, &Theme::new(), ColorChoice::Always).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        to_string_highlighted(&value, &options, &Theme::new())
+    );
+
+    // Output longer than the printer's buffer is coloured in pieces, with the same result.
+    let item = "{ name: \"ünïcode ✓\" since: 2024-01-15T08:30:00Z wait: 1h30m size: 64KiB mode: Mode.Fast n: -1.5e3 }";
+    let value = from_str::<son::Value>(&format!("[{}]", vec![item; 2000].join(" "))).unwrap();
+    for options in [PrinterOptions::compact(), PrinterOptions::new()] {
+        let file = File::create(&path).unwrap();
+        to_writer_highlighted(file, &value, &options, &Theme::new(), ColorChoice::Always).unwrap();
+        assert!(
+            std::fs::read_to_string(&path).unwrap() == to_string_highlighted(&value, &options, &Theme::new()),
+            "streamed output differs"
+        );
+    }
+
+    assert!(!ColorChoice::Never.should_color(&std::io::stdout()));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,3 +1,6 @@
+// The sample data uses 3.14 as an ordinary float, and the bool checks compare with `assert_eq!` like the others.
+#![allow(clippy::approx_constant, clippy::bool_assert_comparison)]
+
 use son::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
 use std::char;
 use std::collections::HashMap;
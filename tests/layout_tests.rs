@@ -0,0 +1,108 @@
+use son::{Commas, PrinterOptions, Value, Wrapping, from_str, son, to_string_with};
+
+fn width(max_width: usize) -> PrinterOptions {
+    return PrinterOptions::new().sort_keys(true).max_width(max_width);
+}
+
+#[test]
+fn test_short_containers_stay_inline() {
+    let value = son!({ name: "api" ports: [80 443] limits: { cpu: 2 memory: 512 } tags: [] });
+    assert_eq!(
+        to_string_with(&value, &width(80)),
+        "{ limits: { cpu: 2 memory: 512 } name: \"api\" ports: [80 443] tags: [] }\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &width(40)),
+        "{\n    limits: { cpu: 2 memory: 512 }\n    name: \"api\"\n    ports: [80 443]\n    tags: []\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &PrinterOptions::new().sort_keys(true)),
+        to_string_with(&value, &width(80))
+    );
+    assert_eq!(
+        to_string_with(&value, &width(0)),
+        to_string_with(&value, &width(80).arrays(Wrapping::Always).objects(Wrapping::Always))
+    );
+}
+
+#[test]
+fn test_lines_never_exceed_the_width() {
+    let matrix: Vec<Vec<f64>> = (0..4)
+        .map(|i| (0..4).map(|j| (i * 4 + j) as f64 / 8.0).collect())
+        .collect();
+    let value = son::to_son(&matrix);
+    let printed = to_string_with(&value, &width(40));
+    assert_eq!(
+        printed,
+        "[\n    [0.0 0.125 0.25 0.375]\n    [0.5 0.625 0.75 0.875]\n    [1.0 1.125 1.25 1.375]\n    [1.5 1.625 1.75 1.875]\n]\n"
+    );
+
+    // An item that is too long on its own still gets its own line.
+    let long = son!({ a: "a string that is far longer than the width" b: [1 2] });
+    let printed = to_string_with(&long, &width(20).commas(Commas::Separators));
+    assert_eq!(
+        printed,
+        "{\n    a: \"a string that is far longer than the width\",\n    b: [1, 2]\n}\n"
+    );
+    for width in [16, 24, 30] {
+        let printed = to_string_with(&value, &self::width(width));
+        assert!(printed.lines().all(|line| line.chars().count() <= width), "{}", printed);
+        assert_eq!(from_str::<Value>(&printed).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_commas_fit_within_the_width() {
+    let value = son!([[1 2] [3 4]]);
+    // `[1, 2],` is seven characters after the indentation, so a width of 11 just fits each row.
+    assert_eq!(
+        to_string_with(&value, &width(11).commas(Commas::Trailing)),
+        "[\n    [1, 2],\n    [3, 4],\n]\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &width(10).commas(Commas::Trailing)),
+        "[\n    [\n        1,\n        2,\n    ],\n    [\n        3,\n        4,\n    ],\n]\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &width(20).commas(Commas::Separators)),
+        "[[1, 2], [3, 4]]\n"
+    );
+}
+
+#[test]
+fn test_per_type_overrides() {
+    let value = son!({ point: { x: 1 y: 2 } path: [[0 0] [1 1]] });
+    assert_eq!(
+        to_string_with(&value, &width(80).objects(Wrapping::Always)),
+        "{\n    path: [[0 0] [1 1]]\n    point: {\n        x: 1\n        y: 2\n    }\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &width(80).arrays(Wrapping::Always)),
+        "{\n    path: [\n        [\n            0\n            0\n        ]\n        [\n            1\n            1\n        ]\n    ]\n    point: { x: 1 y: 2 }\n}\n"
+    );
+
+    // Even with no room at all, `Never` still keeps containers on one line.
+    let options = width(0).arrays(Wrapping::Never);
+    assert_eq!(
+        to_string_with(&value, &options),
+        "{\n    path: [[0 0] [1 1]]\n    point: {\n        x: 1\n        y: 2\n    }\n}\n"
+    );
+}
+
+#[test]
+fn test_width_counts_characters_and_indentation() {
+    let value = son!({ outer: { inner: ["héllo" "wörld"] } });
+    // `        inner: ["héllo" "wörld"]` is 32 characters but 34 bytes, and a tab counts as one character.
+    assert_eq!(
+        to_string_with(&value, &width(32).indent("    ")),
+        "{\n    outer: {\n        inner: [\"héllo\" \"wörld\"]\n    }\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &width(36).indent("\t")),
+        "{\n\touter: { inner: [\"héllo\" \"wörld\"] }\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &PrinterOptions::compact().max_width(10)),
+        "{outer:{inner:[\"héllo\",\"wörld\"]}}"
+    );
+}
@@ -0,0 +1,121 @@
+use son::{ArrayMergeStrategy, MergeOptions, Value};
+
+const BASE: &str = r#"{
+    server: { host: "localhost" port: 8080 workers: 4 }
+    features: ["auth" "cache"]
+    users: [
+        { name: "alice" role: "admin" }
+        { name: "bob" role: "viewer" }
+    ]
+    debug: true
+}"#;
+
+fn parse(source: &str) -> Value {
+    return son::from_str::<Value>(source).unwrap();
+}
+
+fn paths(paths: &[son::Path]) -> Vec<String> {
+    return paths.iter().map(|p| p.to_string()).collect();
+}
+
+#[test]
+fn test_merge_objects_recursively() {
+    let mut base = parse(BASE);
+    let report = base.merge(
+        parse(r#"{ server: { port: 9090 tls: true } debug: true }"#),
+        MergeOptions::new(),
+    );
+
+    assert_eq!(base.query("$.server.port").unwrap()[0].value, &Value::Integer(9090));
+    assert_eq!(
+        base.query("$.server.host").unwrap()[0].value,
+        &Value::String("localhost".to_string())
+    );
+    assert_eq!(base.query("$.server.tls").unwrap()[0].value, &Value::Bool(true));
+    assert_eq!(paths(&report.overridden), vec!["$.server.port"]);
+    assert_eq!(paths(&report.added), vec!["$.server.tls"]);
+    assert!(report.removed.is_empty());
+}
+
+#[test]
+fn test_merge_array_strategies() {
+    let features = |options: MergeOptions| -> Value {
+        let mut base = parse(BASE);
+        base.merge(parse(r#"{ features: ["metrics"] }"#), options);
+        return base.query("$.features").unwrap()[0].value.clone();
+    };
+
+    assert_eq!(features(MergeOptions::new()), parse(r#"["metrics"]"#));
+    assert_eq!(
+        features(MergeOptions::new().arrays(ArrayMergeStrategy::Append)),
+        parse(r#"["auth" "cache" "metrics"]"#)
+    );
+    assert_eq!(
+        features(MergeOptions::new().arrays(ArrayMergeStrategy::MergeByIndex)),
+        parse(r#"["metrics" "cache"]"#)
+    );
+}
+
+#[test]
+fn test_merge_array_by_key() {
+    let mut base = parse(BASE);
+    let report = base.merge(
+        parse(r#"{ users: [ { name: "bob" role: "editor" } { name: "carol" role: "viewer" } ] }"#),
+        MergeOptions::new().arrays(ArrayMergeStrategy::MergeByKey("name".to_string())),
+    );
+
+    assert_eq!(
+        base.query("$.users").unwrap()[0].value,
+        &parse(
+            r#"[
+                { name: "alice" role: "admin" }
+                { name: "bob" role: "editor" }
+                { name: "carol" role: "viewer" }
+            ]"#
+        )
+    );
+    assert_eq!(paths(&report.overridden), vec!["$.users[1].role"]);
+    assert_eq!(paths(&report.added), vec!["$.users[2]"]);
+}
+
+#[test]
+fn test_merge_null_handling() {
+    let mut base = parse(BASE);
+    base.merge(parse(r#"{ debug: null }"#), MergeOptions::new());
+    assert_eq!(base.query("$.debug").unwrap()[0].value, &Value::Null);
+
+    let mut base = parse(BASE);
+    let report = base.merge(
+        parse(r#"{ debug: null server: { workers: null missing: null } }"#),
+        MergeOptions::new().null_deletes(true),
+    );
+    assert!(base.query("$.debug").unwrap().is_empty());
+    assert!(base.query("$.server.workers").unwrap().is_empty());
+    assert!(base.query("$.server.missing").unwrap().is_empty());
+    assert_eq!(paths(&report.removed), vec!["$.debug", "$.server.workers"]);
+
+    // Keys the base doesn't have are added without their nulls.
+    let mut base = parse("{}");
+    let report = base.merge(
+        parse(r#"{ a: { b: null c: [{ d: null }] } e: [1 null] }"#),
+        MergeOptions::new().null_deletes(true),
+    );
+    assert_eq!(base, parse(r#"{ a: { c: [{}] } e: [1 null] }"#));
+    assert_eq!(paths(&report.added), vec!["$.a", "$.e"]);
+}
+
+#[test]
+fn test_merge_type_mismatch_replaces() {
+    let mut base = parse(BASE);
+    let report = base.merge(parse(r#"{ server: "remote" }"#), MergeOptions::new());
+    assert_eq!(
+        base.query("$.server").unwrap()[0].value,
+        &Value::String("remote".to_string())
+    );
+    assert_eq!(paths(&report.overridden), vec!["$.server"]);
+
+    let mut base = parse(BASE);
+    let report = base.merge(parse(BASE), MergeOptions::new());
+    assert_eq!(base, parse(BASE));
+    assert!(report.is_unchanged());
+}
@@ -0,0 +1,128 @@
+use son::{DeserializationError, Deserialize, FromSon, Number, ParseOptions, ToSon, Value};
+use std::str::FromStr;
+
+const INVOICE: &str = r#"{
+    price: 19.10
+    quantity: 3
+    discount: -0.05
+    precise: 3.14159265358979323846264338327950288
+    total: 57.300
+}"#;
+
+// A minimal fixed-point decimal with two fractional digits, standing in for a user-supplied decimal type.
+#[derive(Debug, Clone, PartialEq)]
+struct Cents(i64);
+
+impl FromStr for Cents {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, "0"));
+        let fraction = format!("{:0<2}", fraction);
+        if fraction.len() > 2 {
+            return Err(format!("{} has more than two decimals", s));
+        }
+        let sign = if integer.starts_with('-') { -1 } else { 1 };
+        let integer = integer.parse::<i64>().map_err(|e| e.to_string())?;
+        let fraction = fraction.parse::<i64>().map_err(|e| e.to_string())?;
+        return Ok(Cents(integer * 100 + sign * fraction));
+    }
+}
+
+impl Deserialize for Cents {}
+impl FromSon for Cents {
+    fn from_son(son: Value) -> Result<Self, DeserializationError> {
+        return Number::from_son(son)?
+            .parse::<Cents>()
+            .map_err(|message| DeserializationError::InvalidValue { message });
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+struct Invoice {
+    price: Cents,
+    quantity: u8,
+    discount: f64,
+    precise: Number,
+    total: f64,
+}
+
+fn preserve() -> ParseOptions {
+    return ParseOptions::new().preserve_numbers(true);
+}
+
+fn field(value: &Value, key: &str) -> Value {
+    return value.get_path(&son::Path::root().key(key)).unwrap().clone();
+}
+
+#[test]
+fn test_numbers_are_only_preserved_on_request() {
+    let value = son::from_str::<Value>(INVOICE).unwrap();
+    assert_eq!(field(&value, "price"), Value::Float(19.1));
+    assert_eq!(field(&value, "quantity"), Value::Integer(3));
+
+    let value = son::from_str_with::<Value>(INVOICE, preserve()).unwrap();
+    assert_eq!(field(&value, "price"), Value::Number(Number::new("19.10").unwrap()));
+    assert_eq!(field(&value, "discount").get_type(), "Number");
+    assert_eq!(
+        field(&value, "precise"),
+        Value::Number(Number::new("3.14159265358979323846264338327950288").unwrap())
+    );
+}
+
+#[test]
+fn test_numbers_print_losslessly() {
+    let value = son::from_str_with::<Value>(INVOICE, preserve()).unwrap();
+    let printed = value.to_string();
+    assert!(printed.contains("price: 19.10\n"));
+    assert!(printed.contains("discount: -0.05\n"));
+    assert!(printed.contains("precise: 3.14159265358979323846264338327950288\n"));
+
+    let reparsed = son::from_str_with::<Value>(&printed, preserve()).unwrap();
+    assert_eq!(reparsed, value);
+}
+
+#[test]
+fn test_number_conversions() {
+    let number = |literal: &str| Number::new(literal).unwrap();
+
+    assert_eq!(number("42").to_i128(), Some(42));
+    assert_eq!(number("-42.000").to_i128(), Some(-42));
+    assert_eq!(number("42.5").to_i128(), None);
+    assert_eq!(
+        number("340282366920938463463374607431768211455").to_u128(),
+        Some(u128::MAX)
+    );
+    assert_eq!(number("19.10").to_f64(), 19.1);
+    assert!(number("300").to_integer::<u8>().is_err());
+    assert_eq!(number("255").to_integer::<u8>().unwrap(), 255);
+    assert!(number("-1").to_integer::<u32>().is_err());
+    assert_eq!(number("19.10").parse::<Cents>(), Ok(Cents(1910)));
+    assert_eq!(number("1.5").negate().as_str(), "-1.5");
+
+    assert!(Number::new("1e5").is_none());
+    assert!(Number::new("abc").is_none());
+    assert!(Number::new(".5").is_none());
+
+    assert_eq!(u8::from_son(Value::Number(number("7"))).unwrap(), 7);
+    assert!(u8::from_son(Value::Number(number("7.5"))).is_err());
+    assert_eq!(f32::from_son(Value::Number(number("0.25"))).unwrap(), 0.25);
+    assert_eq!(Number::from_son(Value::Integer(12)).unwrap(), number("12"));
+}
+
+#[test]
+fn test_deserialize_exact_numbers() {
+    let invoice: Invoice = son::from_str_with(INVOICE, preserve()).unwrap();
+    assert_eq!(invoice.price, Cents(1910));
+    assert_eq!(invoice.quantity, 3);
+    assert_eq!(invoice.discount, -0.05);
+    assert_eq!(invoice.precise.as_str(), "3.14159265358979323846264338327950288");
+    assert_eq!(invoice.total, 57.3);
+    assert_eq!(invoice.precise.to_son(), Value::Number(invoice.precise.clone()));
+
+    let result = son::from_str_with::<Invoice>(&INVOICE.replace("quantity: 3", "quantity: 3.5"), preserve());
+    assert!(result.is_err());
+
+    let result = son::from_str_with::<Invoice>(&INVOICE.replace("19.10", "19.105"), preserve());
+    assert!(result.is_err());
+}
@@ -0,0 +1,116 @@
+use son::{
+    Commas, LineEnding, PrinterOptions, Value, Wrapping, from_str, son, to_string, to_string_pretty, to_string_with,
+};
+
+// The pretty layout with every container broken up, one item per line.
+fn expanded() -> PrinterOptions {
+    return PrinterOptions::new()
+        .sort_keys(true)
+        .arrays(Wrapping::Always)
+        .objects(Wrapping::Always);
+}
+
+#[test]
+fn test_compact_output() {
+    let value = son!({ b: [1 -2 3.5] a: { x: "text" y: [] z: {} } c: Active });
+    assert_eq!(
+        to_string_with(&value, &PrinterOptions::compact().sort_keys(true)),
+        r#"{a:{x:"text",y:[],z:{}},b:[1,-2,3.5],c:Active}"#
+    );
+    assert_eq!(to_string(&son!([[1 2] [] null])), "[[1,2],[],null]");
+    assert_eq!(to_string(&son!({ only: true })), "{only:true}");
+}
+
+#[test]
+fn test_pretty_output() {
+    let value = son!({ name: "api" ports: [80 443] limits: {} tags: [] });
+    assert_eq!(
+        to_string_with(&value, &expanded()),
+        "{\n    limits: {}\n    name: \"api\"\n    ports: [\n        80\n        443\n    ]\n    tags: []\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&son!({ ports: [80] }), &expanded().indent("\t")),
+        "{\n\tports: [\n\t\t80\n\t]\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &PrinterOptions::new().sort_keys(true)),
+        "{ limits: {} name: \"api\" ports: [80 443] tags: [] }\n"
+    );
+    assert_eq!(to_string_pretty(&son!({ ports: [80] }), "  "), "{ ports: [80] }\n");
+    assert_eq!(son!({ ports: [80] }).to_string(), "{ ports: [80] }\n");
+}
+
+#[test]
+fn test_commas() {
+    let value = son!({ a: [1 2] b: 3 });
+    assert_eq!(
+        to_string_with(&value, &expanded().commas(Commas::Separators)),
+        "{\n    a: [\n        1,\n        2\n    ],\n    b: 3\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &expanded().commas(Commas::Trailing)),
+        "{\n    a: [\n        1,\n        2,\n    ],\n    b: 3,\n}\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &PrinterOptions::compact().sort_keys(true).commas(Commas::None)),
+        "{a:[1 2] b:3}"
+    );
+    assert_eq!(
+        to_string_with(
+            &value,
+            &PrinterOptions::compact().sort_keys(true).commas(Commas::Trailing)
+        ),
+        "{a:[1,2,],b:3,}"
+    );
+}
+
+#[test]
+fn test_spacing_and_line_endings() {
+    let value = son!({ a: [1] });
+    assert_eq!(
+        to_string_with(&value, &expanded().line_ending(LineEnding::CrLf)),
+        "{\r\n    a: [\r\n        1\r\n    ]\r\n}\r\n"
+    );
+    assert_eq!(
+        to_string_with(&value, &expanded().space_after_colon(false).final_newline(false)),
+        "{\n    a:[\n        1\n    ]\n}"
+    );
+    assert_eq!(
+        to_string_with(
+            &value,
+            &PrinterOptions::compact().space_after_colon(true).final_newline(true)
+        ),
+        "{a: [1]}\n"
+    );
+}
+
+#[test]
+fn test_every_layout_parses_back() {
+    let value: Value = from_str(
+        r#"{
+        name: "api" port: -8080 ratio: 0.25 huge: 340282366920938463463374607431768211455
+        letter: 'x' mode: Status.Fast enabled: false missing: null
+        started: 2024-06-01T08:30:00Z timeout: 1h30m buffer: 64KiB payload: b"\x01\x02"
+        nested: { empty: {} items: [[] [1 2] { a: 1 }] }
+    }"#,
+    )
+    .unwrap();
+
+    for pretty in [true, false] {
+        for commas in [Commas::None, Commas::Separators, Commas::Trailing] {
+            for space_after_colon in [true, false] {
+                for line_ending in [LineEnding::Lf, LineEnding::CrLf] {
+                    let options = PrinterOptions::new()
+                        .pretty(pretty)
+                        .commas(commas)
+                        .space_after_colon(space_after_colon)
+                        .line_ending(line_ending)
+                        .qualify_enums(true);
+                    let printed = to_string_with(&value, &options);
+                    assert_eq!(from_str::<Value>(&printed).unwrap(), value, "{}", printed);
+                    assert_eq!(printed.contains('\n'), pretty || options.final_newline);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use son::{Path, Query, Value};
+use std::collections::HashMap;
+
+const SERVERS: &str = r#"{
+    name: "cluster"
+    servers: [
+        { host: "alpha" port: 8080 enabled: true tags: ["web" "edge"] }
+        { host: "beta" port: 9090 enabled: false tags: [] }
+        { host: "gamma" port: 7070 enabled: true status: Active }
+    ]
+    database: { port: 5432 max_connections: 20 }
+}"#;
+
+fn document() -> Value {
+    return son::from_str::<Value>(SERVERS).unwrap();
+}
+
+fn paths(value: &Value, query: &str) -> Vec<String> {
+    return value.query(query).unwrap().iter().map(|m| m.path.to_string()).collect();
+}
+
+#[test]
+fn test_query_child_and_index() {
+    let document = document();
+
+    let matches = document.query("$.servers[1].host").unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].value, &Value::String("beta".to_string()));
+    assert_eq!(matches[0].path.to_string(), "$.servers[1].host");
+
+    assert_eq!(paths(&document, "$.servers[-1].host"), vec!["$.servers[2].host"]);
+    assert_eq!(
+        paths(&document, "$['database']['max_connections']"),
+        vec!["$.database.max_connections"]
+    );
+    assert!(paths(&document, "$.servers[5]").is_empty());
+    assert!(paths(&document, "$.name.missing").is_empty());
+}
+
+#[test]
+fn test_query_wildcards_and_recursive_descent() {
+    let document = document();
+
+    assert_eq!(
+        paths(&document, "$.servers[*].port"),
+        vec!["$.servers[0].port", "$.servers[1].port", "$.servers[2].port"]
+    );
+    assert_eq!(
+        paths(&document, "$..port"),
+        vec![
+            "$.database.port",
+            "$.servers[0].port",
+            "$.servers[1].port",
+            "$.servers[2].port"
+        ]
+    );
+    assert_eq!(paths(&document, "$.servers[0].tags.*").len(), 2);
+    assert_eq!(paths(&document, "$..tags[0]"), vec!["$.servers[0].tags[0]"]);
+}
+
+#[test]
+fn test_query_slices_and_unions() {
+    let value = son::from_str::<Value>("[0 1 2 3 4 5]").unwrap();
+    let integers = |query: &str| -> Vec<i128> {
+        return Query::parse(query)
+            .unwrap()
+            .select_values(&value)
+            .into_iter()
+            .map(|v| match v {
+                Value::Integer(i) => *i,
+                _ => panic!("Expected an integer"),
+            })
+            .collect();
+    };
+
+    assert_eq!(integers("$[1:3]"), vec![1, 2]);
+    assert_eq!(integers("$[:2]"), vec![0, 1]);
+    assert_eq!(integers("$[4:]"), vec![4, 5]);
+    assert_eq!(integers("$[::2]"), vec![0, 2, 4]);
+    assert_eq!(integers("$[::-1]"), vec![5, 4, 3, 2, 1, 0]);
+    assert_eq!(integers("$[-2:]"), vec![4, 5]);
+    assert_eq!(integers("$[0, 3, -1]"), vec![0, 3, 5]);
+    assert!(integers("$[1:5:0]").is_empty());
+    assert_eq!(integers("$[1::9223372036854775807]"), vec![1]);
+    assert_eq!(integers("$[-2::-9223372036854775808]"), vec![4]);
+}
+
+#[test]
+fn test_query_filters() {
+    let document = document();
+
+    assert_eq!(
+        paths(&document, "$.servers[?@.enabled == true].port"),
+        vec!["$.servers[0].port", "$.servers[2].port"]
+    );
+    assert_eq!(
+        paths(&document, "$.servers[?(@.port > 7070 && @.port <= 9090)].host"),
+        vec!["$.servers[0].host", "$.servers[1].host"]
+    );
+    assert_eq!(
+        paths(&document, "$.servers[?@.host == 'beta' || @.status == Active].host"),
+        vec!["$.servers[1].host", "$.servers[2].host"]
+    );
+    assert_eq!(paths(&document, "$.servers[?@.status]"), vec!["$.servers[2]"]);
+    assert_eq!(paths(&document, "$.servers[?!@.status].host").len(), 2);
+    assert_eq!(paths(&document, "$.servers[?@.port != $.database.port]").len(), 3);
+    assert_eq!(paths(&document, "$.servers[?@.port == 8080.0]"), vec!["$.servers[0]"]);
+    assert_eq!(paths(&document, "$..[?@ == 'edge']"), vec!["$.servers[0].tags[1]"]);
+}
+
+#[test]
+fn test_query_invalid_syntax() {
+    let invalid = [
+        "servers",
+        "$.",
+        "$[",
+        "$[1",
+        "$['name",
+        "$[?@.port >]",
+        "$[?5]",
+        "$[?@..port == 1]",
+        "$.servers extra",
+    ];
+    for query in invalid {
+        assert!(Query::parse(query).is_err(), "Expected an error for {}", query);
+    }
+
+    let error = Query::parse("$.servers[?@.port >]").unwrap_err();
+    assert_eq!(error.position, 19);
+}
+
+#[test]
+fn test_query_paths_resolve_to_matches() {
+    let document = document();
+    for m in document.query("$..*").unwrap() {
+        assert_eq!(document.get_path(&m.path), Some(m.value));
+    }
+    assert_eq!(document.get_path(&Path::root()), Some(&document));
+}
+
+#[test]
+fn test_query_quoted_member_names() {
+    let mut map = HashMap::new();
+    map.insert("max connections".to_string(), Value::Integer(20));
+    let value = Value::Object(map);
+
+    assert_eq!(paths(&value, "$[\"max connections\"]"), vec!["$[\"max connections\"]"]);
+    assert!(paths(&value, "$.max").is_empty());
+}
@@ -0,0 +1,115 @@
+use son::{
+    DeserializationError, Deserialize, FromSon, ParseOptions, Path, RawSon, Serialize, ToSon, Value, from_str,
+    from_str_with, son, to_string,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    id: i64,
+    kind: String,
+    payload: RawSon,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Order {
+    item: String,
+    quantity: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Batch {
+    name: String,
+    envelope: Envelope,
+    extra: Option<RawSon>,
+}
+
+const MESSAGE: &str = r#"{
+    id: 7
+    kind: "order"
+    payload: {
+        // Set by the storefront.
+        item:     "lamp"
+        quantity: 2
+    }
+}"#;
+
+const PAYLOAD: &str = r#"{
+        // Set by the storefront.
+        item:     "lamp"
+        quantity: 2
+    }"#;
+
+#[test]
+fn test_raw_field_keeps_source_text() {
+    let envelope: Envelope = from_str(MESSAGE).unwrap();
+    assert_eq!(envelope.id, 7);
+    assert_eq!(envelope.payload.as_str(), PAYLOAD);
+
+    let order: Order = envelope.payload.parse().unwrap();
+    assert_eq!(
+        order,
+        Order {
+            item: "lamp".to_string(),
+            quantity: 2
+        }
+    );
+}
+
+#[test]
+fn test_raw_field_is_serialized_verbatim() {
+    let envelope: Envelope = from_str(MESSAGE).unwrap();
+    let output = to_string(&envelope);
+    assert!(output.contains(&format!("payload:{}", PAYLOAD)), "{}", output);
+
+    let reparsed: Envelope = from_str(&output).unwrap();
+    assert_eq!(reparsed.payload, envelope.payload);
+}
+
+#[test]
+fn test_raw_fields_in_nested_structs_and_other_forms() {
+    let input = r#"{
+        name: "nightly"
+        envelope {
+            id: 1 kind: "note"
+            payload [1 2 /* three */ 3]
+        }
+        extra: -42
+    }"#;
+    let batch: Batch = from_str(input).unwrap();
+    assert_eq!(batch.name, "nightly");
+    assert_eq!(batch.envelope.payload.as_str(), "[1 2 /* three */ 3]");
+    assert_eq!(batch.extra.as_ref().map(RawSon::as_str), Some("-42"));
+    assert_eq!(batch.envelope.payload.parse::<Vec<i64>>().unwrap(), vec![1, 2, 3]);
+
+    let batch: Batch =
+        from_str(r#"{ name: "empty" envelope: { id: 2 kind: "x" payload: "text" } extra: null }"#).unwrap();
+    assert_eq!(batch.envelope.payload.as_str(), "\"text\"");
+    assert!(batch.extra.is_none());
+}
+
+#[test]
+fn test_raw_son_from_text_and_values() {
+    let raw = RawSon::new("{ a: 1 } // trailing comment").unwrap();
+    assert_eq!(raw.parse::<Value>().unwrap(), son!({ a: 1 }));
+    assert!(RawSon::new("{ a: 1 } { b: 2 }").is_err());
+    assert!(RawSon::new("{ a: ").is_err());
+    // Text that closes the wrapping array early must not leave trailing junk behind.
+    assert!(RawSon::new("1] {oops").is_err());
+    assert!(RawSon::new("1] [2").is_err());
+    assert!(RawSon::new("[1]").unwrap().parse::<Vec<i64>>().is_ok());
+
+    let raw = RawSon::from_son(son!([1 2])).unwrap();
+    assert_eq!(raw.parse::<Vec<i64>>().unwrap(), vec![1, 2]);
+    assert_eq!(raw.to_son(), Value::Raw(raw.clone()));
+}
+
+#[test]
+fn test_raw_paths_option() {
+    let options = ParseOptions::new().raw_paths(vec![Path::root().key("payload").key("item")]);
+    let value: Value = from_str_with(MESSAGE, options).unwrap();
+    let item = &value.query("$.payload.item").unwrap()[0];
+    assert_eq!(item.value, &Value::Raw(RawSon::new("\"lamp\"").unwrap()));
+
+    let whole: RawSon = from_str(MESSAGE).unwrap();
+    assert_eq!(whole.as_str(), MESSAGE);
+}
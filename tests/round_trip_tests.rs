@@ -0,0 +1,297 @@
+use son::{
+    BigInt, ByteSize, Commas, DateTime, EnumVariant, LineEnding, PrinterOptions, Serialize, ToSon, Value, Wrapping,
+    from_str, son, to_son, to_string, to_string_with,
+};
+use std::{collections::HashMap, time::Duration};
+
+// A small xorshift generator, so failures reproduce from the seed.
+struct Generator {
+    state: u64,
+}
+
+const CHARS: &[char] = &[
+    'a', 'Z', '0', '_', ' ', '"', '\'', '\\', '/', '*', '{', '}', '[', ']', ':', ',', 'u', 'n', '\n', '\r', '\t', '\0',
+    '\u{7}', '\u{1b}', '\u{7f}', '\u{85}', '\u{2028}', 'é', 'ß', '中', '😀',
+];
+
+const KEYS: &[&str] = &[
+    "name",
+    "_private",
+    "café",
+    "",
+    " ",
+    "two words",
+    "true",
+    "false",
+    "null",
+    "inf",
+    "NaN",
+    "1st",
+    "a.b",
+    "a::b",
+    "b",
+    "b64",
+    "x-y",
+    "\"",
+    "'",
+    "\\",
+    "\n",
+    "//",
+    "$",
+    "中文",
+];
+
+const KEYWORDS: &[&str] = &["true", "false", "null", "inf", "NaN"];
+
+const DATETIMES: &[&str] = &[
+    "2024-01-15",
+    "08:30:00",
+    "23:59:59.999",
+    "2024-01-15T08:30:00Z",
+    "2024-02-29T00:00:00.123456789+05:30",
+    "1999-12-31T23:59:59-08:00",
+];
+
+impl Generator {
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        return (self.next() % bound as u64) as usize;
+    }
+
+    fn text(&mut self) -> String {
+        let length = self.below(8);
+        return (0..length).map(|_| CHARS[self.below(CHARS.len())]).collect();
+    }
+
+    // Identifiers, including keywords and names that start like a keyword or a bytes literal.
+    fn identifier(&mut self) -> String {
+        let first = ["a", "Z", "_", "q", "inf", "NaN", "true", "false", "null", "b", "b64"];
+        let rest = ['a', 'Z', '_', '0', '9', 'é'];
+        let mut identifier = first[self.below(first.len())].to_string();
+        for _ in 0..self.below(6) {
+            identifier.push(rest[self.below(rest.len())]);
+        }
+        return identifier;
+    }
+
+    fn float(&mut self) -> f64 {
+        let special = [
+            0.0,
+            -0.0,
+            1.0,
+            -1.0,
+            0.1,
+            1e16,
+            1e-7,
+            123456.789,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            0.1f32 as f64,
+        ];
+        return match self.below(3) {
+            0 => special[self.below(special.len())],
+            1 => f64::from_bits(self.next()),
+            _ => (self.next() % 100_000) as f64 / 100.0,
+        };
+    }
+
+    fn integer(&mut self) -> i128 {
+        return match self.below(3) {
+            0 => [0, 1, -1, i128::MAX, i128::MIN][self.below(5)],
+            1 => ((self.next() as i128) << 64 | self.next() as i128) >> self.below(100),
+            _ => self.below(1000) as i128 - 500,
+        };
+    }
+
+    fn big_integer(&mut self) -> Value {
+        let mut digits = if self.below(2) == 0 {
+            "-1".to_string()
+        } else {
+            "9".to_string()
+        };
+        for _ in 0..40 + self.below(20) {
+            digits.push(char::from(b'0' + self.below(10) as u8));
+        }
+        return Value::from(digits.parse::<BigInt>().unwrap());
+    }
+
+    fn scalar(&mut self) -> Value {
+        return match self.below(13) {
+            0 => Value::Null,
+            1 => Value::Bool(self.below(2) == 0),
+            2 => Value::Float(self.float()),
+            3 => Value::Integer(self.integer()),
+            4 => self.big_integer(),
+            5 => Value::String(self.text()),
+            6 => Value::Char(CHARS[self.below(CHARS.len())]),
+            // Keywords are reserved, so they are only variant names when qualified.
+            7 => match self.identifier() {
+                name if KEYWORDS.contains(&name.as_str()) => Value::Enum(EnumVariant::qualified("Keyword", &name)),
+                name => Value::Enum(EnumVariant::new(&name)),
+            },
+            8 => Value::Enum(EnumVariant::qualified(&self.identifier(), &self.identifier())),
+            9 => Value::Bytes((0..self.below(6)).map(|_| self.next() as u8).collect()),
+            10 => Value::DateTime(DATETIMES[self.below(DATETIMES.len())].parse::<DateTime>().unwrap()),
+            11 => Value::Duration(Duration::new(
+                self.next() % 10_000_000,
+                (self.next() % 1_000_000_000) as u32,
+            )),
+            _ => Value::ByteSize(ByteSize::from(self.next() >> self.below(64))),
+        };
+    }
+
+    fn value(&mut self, depth: usize) -> Value {
+        if depth == 0 {
+            return self.scalar();
+        }
+        return match self.below(4) {
+            0 => self.array(depth - 1),
+            1 => self.object(depth - 1),
+            _ => self.scalar(),
+        };
+    }
+
+    fn array(&mut self, depth: usize) -> Value {
+        return Value::Array((0..self.below(5)).map(|_| self.value(depth)).collect());
+    }
+
+    fn object(&mut self, depth: usize) -> Value {
+        let mut object = HashMap::new();
+        for _ in 0..self.below(5) {
+            let key = match self.below(2) {
+                0 => KEYS[self.below(KEYS.len())].to_string(),
+                _ => self.text(),
+            };
+            object.insert(key, self.value(depth));
+        }
+        return Value::Object(object);
+    }
+}
+
+#[test]
+fn test_print_then_parse_round_trips() {
+    // Qualifiers are part of a variant's identity, so every layout keeps them.
+    let layouts = [
+        PrinterOptions::new(),
+        PrinterOptions::compact(),
+        PrinterOptions::compact().commas(Commas::None),
+        PrinterOptions::new().max_width(30),
+        PrinterOptions::new().arrays(Wrapping::Always).objects(Wrapping::Always),
+        PrinterOptions::new()
+            .commas(Commas::Trailing)
+            .line_ending(LineEnding::CrLf)
+            .indent("\t"),
+    ]
+    .map(|options| options.qualify_enums(true));
+    let mut generator = Generator {
+        state: 0x2545f4914f6cdd1d,
+    };
+    for _ in 0..2000 {
+        let value = match generator.below(2) {
+            0 => generator.array(4),
+            _ => generator.object(4),
+        };
+        for options in &layouts {
+            let printed = to_string_with(&value, options);
+            let parsed = from_str::<Value>(&printed).unwrap_or_else(|e| panic!("{}\n{}", e, printed));
+            assert_eq!(parsed, value, "{}", printed);
+            // The canonical text spells out enum qualifiers, so a printer dropping them fails here as well.
+            assert_eq!(parsed.to_canonical_string(), value.to_canonical_string(), "{}", printed);
+        }
+    }
+}
+
+#[test]
+fn test_strings_and_chars_are_escaped() {
+    let value = son!(["say \"hi\"\n\tC:\\temp\r" '\'' '\\' '\n']);
+    let printed = to_string(&value);
+    assert_eq!(printed, r#"["say \"hi\"\n\tC:\\temp\r",'\'','\\','\n']"#);
+    assert_eq!(from_str::<Value>(&printed).unwrap(), value);
+
+    let control = Value::Array(vec![Value::String("bell\u{7}".to_string()), Value::Char('\0')]);
+    assert_eq!(to_string(&control), r#"["bell\u{7}",'\u{0}']"#);
+    assert_eq!(
+        from_str::<Value>(r#"["\u{1F600}" '\u{e9}' "\0"]"#).unwrap(),
+        son!(["😀" 'é' "\0"])
+    );
+    assert!(from_str::<Value>(r#"["\u{110000}"]"#).is_err());
+    assert!(from_str::<Value>(r"['\u{41']").is_err());
+}
+
+#[test]
+fn test_floats_keep_their_type() {
+    let value = son!([1.0 -2.0 0.1 100000.0]);
+    assert_eq!(to_string(&value), "[1.0,-2.0,0.1,100000.0]");
+
+    let extremes = Value::Array(vec![
+        Value::Float(1e16),
+        Value::Float(1.5e-7),
+        Value::Float(f64::MAX),
+        Value::Float(f64::INFINITY),
+        Value::Float(f64::NEG_INFINITY),
+        Value::Float(f64::NAN),
+    ]);
+    assert_eq!(
+        to_string(&extremes),
+        "[1e16,1.5e-7,1.7976931348623157e308,inf,-inf,NaN]"
+    );
+    assert_eq!(from_str::<Value>(&to_string(&extremes)).unwrap(), extremes);
+
+    assert_eq!(
+        from_str::<Value>("[2E3 1e+2 1E-1 64PiB]").unwrap(),
+        son!([2000.0 100.0 0.1 #(ByteSize::from(64u64 << 50))])
+    );
+
+    // `inf` and `NaN` are floats in `son!` too, and variants with those names keep their qualifier.
+    assert_eq!(son!([inf -inf NaN]), from_str::<Value>("[inf -inf NaN]").unwrap());
+    let keywords = Value::Array(vec![
+        Value::Enum(EnumVariant::qualified("Float", "NaN")),
+        Value::Enum(EnumVariant::qualified("Float", "inf")),
+    ]);
+    let printed = to_string_with(&keywords, &PrinterOptions::compact().qualify_enums(false));
+    assert_eq!(printed, "[Float.NaN,Float.inf]");
+    assert_eq!(from_str::<Value>(&printed).unwrap(), keywords);
+}
+
+#[test]
+fn test_keys_are_quoted_when_needed() {
+    let mut object = HashMap::new();
+    for key in ["plain", "two words", "true", "", "1st", "a.b"] {
+        object.insert(key.to_string(), Value::Integer(1));
+    }
+    let value = Value::Object(object);
+    let printed = to_string_with(&value, &PrinterOptions::compact().sort_keys(true));
+    assert_eq!(printed, r#"{"":1,"1st":1,"a.b":1,plain:1,"true":1,"two words":1}"#);
+    assert_eq!(from_str::<Value>(&printed).unwrap(), value);
+
+    assert!(from_str::<Value>(r#"{ "key" "other": 1 }"#).is_err());
+}
+
+#[derive(Serialize)]
+struct Sample {
+    ratio: f32,
+    weights: Vec<f32>,
+}
+
+#[test]
+fn test_f32_values_print_as_written() {
+    let sample = Sample {
+        ratio: 0.1,
+        weights: vec![1.0, 0.3, f32::MAX, f32::MIN_POSITIVE, f32::NAN],
+    };
+    assert_eq!(to_son(&0.1f32), Value::Float(0.1));
+    assert_eq!(to_string(&sample.weights), "[1.0,0.3,3.4028235e38,1.1754944e-38,NaN]");
+
+    let parsed: Value = from_str(&to_string(&sample)).unwrap();
+    assert_eq!(parsed, to_son(&sample));
+}
@@ -0,0 +1,117 @@
+use son::{FileOptions, PrinterOptions, Value, from_file, son, to_file, to_file_pretty, to_file_with};
+use std::{fs, path::PathBuf};
+
+// A fresh directory for each test, removed when the test finishes.
+struct Directory(PathBuf);
+
+impl Directory {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("son-save-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        return Directory(path);
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        return self.0.join(name);
+    }
+
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&self.0)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        return names;
+    }
+}
+
+impl Drop for Directory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_save_and_load() {
+    let directory = Directory::new("load");
+    let value = son!({ host: "localhost" port: 8080 tags: ["a" "b"] });
+
+    to_file(directory.file("compact.son"), &value).unwrap();
+    assert_eq!(
+        fs::read_to_string(directory.file("compact.son")).unwrap(),
+        son::to_string(&value)
+    );
+
+    let path = directory.file("pretty.son");
+    to_file_pretty(&path, &value, "  ").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), son::to_string_pretty(&value, "  "));
+    assert_eq!(from_file::<Value>(path.to_str().unwrap()).unwrap(), value);
+    assert_eq!(directory.names(), ["compact.son", "pretty.son"]);
+}
+
+#[test]
+fn test_overwrite_leaves_no_temporary_files() {
+    let directory = Directory::new("overwrite");
+    let path = directory.file("config.son");
+    for port in 0..5 {
+        to_file(&path, &son!({ port: #(port) })).unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{port:4}");
+    assert_eq!(directory.names(), ["config.son"]);
+}
+
+#[test]
+fn test_backup_keeps_the_previous_version() {
+    let directory = Directory::new("backup");
+    let path = directory.file("config.son");
+    let options = FileOptions::new().printer(PrinterOptions::compact()).backup(true);
+
+    // There is nothing to back up the first time.
+    to_file_with(&path, &son!({ version: 1 }), &options).unwrap();
+    assert_eq!(directory.names(), ["config.son"]);
+
+    to_file_with(&path, &son!({ version: 2 }), &options).unwrap();
+    to_file_with(&path, &son!({ version: 3 }), &options).unwrap();
+    assert_eq!(directory.names(), ["config.son", "config.son.bak"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{version:3}");
+    assert_eq!(
+        fs::read_to_string(directory.file("config.son.bak")).unwrap(),
+        "{version:2}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_permissions_are_preserved() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let directory = Directory::new("permissions");
+    let path = directory.file("secret.son");
+    fs::write(&path, "{}").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+    to_file(&path, &son!({ token: "abc" })).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o604)).unwrap();
+    to_file_with(&path, &son!({}), &FileOptions::new().preserve_permissions(false)).unwrap();
+    assert_ne!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o604);
+}
+
+#[test]
+fn test_failed_writes_change_nothing() {
+    let directory = Directory::new("failure");
+    let missing = directory.file("missing").join("config.son");
+    assert!(matches!(to_file(&missing, &son!([1])), Err(son::Error::IOError(_))));
+
+    // Renaming over a directory fails after the temporary file was written, which must then be removed.
+    fs::create_dir(directory.file("taken")).unwrap();
+    assert!(matches!(
+        to_file(directory.file("taken"), &son!([1])),
+        Err(son::Error::IOError(_))
+    ));
+    assert_eq!(directory.names(), ["taken"]);
+
+    assert!(to_file("/", &son!([1])).is_err());
+}
@@ -0,0 +1,45 @@
+use son::{DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub database: Database,
+    pub labels: std::collections::HashMap<String, Value>,
+    pub mode: Level,
+    pub name: String,
+    pub port: i64,
+    pub replicas: Vec<Replica>,
+    pub status: Status,
+    pub tags: Vec<String>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Database {
+    pub host: String,
+    pub timeout: Option<std::time::Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Level {
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replica {
+    pub region: Region,
+    pub weight: f64,
+    pub zone: Option<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Region {
+    EuWest,
+    UsEast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    Active,
+    Draining,
+}
@@ -0,0 +1,152 @@
+use son::{Field, Schema, Value, from_str, son, to_string};
+use std::collections::{BTreeMap, BTreeSet};
+
+// The output of `Schema::to_rust` for the samples below, compiled as part of this test.
+mod generated {
+    include!("schema/config.rs");
+}
+
+const SAMPLES: [&str; 2] = [
+    r#"{
+        name: "api" port: 8080 status: Active mode: Level.High type: "svc"
+        database: { host: "db" timeout: 30s }
+        replicas: [{ region: EuWest weight: 1 }]
+        tags: []
+        labels: {}
+    }"#,
+    r#"{
+        name: "web" port: 80 status: Draining mode: Low
+        database: { host: "db2" timeout: null }
+        replicas: [{ region: UsEast weight: 0.5 zone: 'a' }]
+        tags: ["edge"]
+        labels: {}
+    }"#,
+];
+
+fn samples() -> Vec<Value> {
+    return SAMPLES.iter().map(|sample| from_str(sample).unwrap()).collect();
+}
+
+fn field(schema: Schema, optional: bool) -> Field {
+    return Field { schema, optional };
+}
+
+#[test]
+fn test_infer_unifies_samples() {
+    let schema = Schema::infer_all(&[son!({ a: 1 b: Red c: null }), son!({ a: 2.5 b: Blue c: "x" d: [] })]);
+    let expected = Schema::Object(BTreeMap::from([
+        ("a".to_string(), field(Schema::Float, false)),
+        (
+            "b".to_string(),
+            field(
+                Schema::Enum {
+                    type_name: None,
+                    variants: BTreeSet::from(["Blue".to_string(), "Red".to_string()]),
+                },
+                false,
+            ),
+        ),
+        (
+            "c".to_string(),
+            field(Schema::Nullable(Box::new(Schema::String)), false),
+        ),
+        ("d".to_string(), field(Schema::Array(Box::new(Schema::Unknown)), true)),
+    ]));
+    assert_eq!(schema, expected);
+}
+
+#[test]
+fn test_unify_rules() {
+    let integer = |i: i128| Schema::Integer { min: i, max: i };
+    assert_eq!(integer(-1).unify(integer(5)), Schema::Integer { min: -1, max: 5 });
+    assert_eq!(Schema::String.unify(integer(1)), Schema::Any);
+    assert_eq!(Schema::Any.unify(Schema::Null), Schema::Any);
+    assert_eq!(Schema::Null.unify(Schema::Null), Schema::Null);
+    assert_eq!(
+        Schema::Null.unify(Schema::Bool).unify(Schema::Null),
+        Schema::Nullable(Box::new(Schema::Bool))
+    );
+    assert_eq!(Schema::infer(&son!([1 "two"])), Schema::Array(Box::new(Schema::Any)));
+    assert_eq!(Schema::infer_all(&[]), Schema::Unknown);
+}
+
+#[test]
+fn test_generated_rust_matches_fixture() {
+    let schema = Schema::infer_all(&samples());
+    assert_eq!(schema.to_rust("config"), include_str!("schema/config.rs"));
+}
+
+#[test]
+fn test_generated_types_load_samples() {
+    for sample in SAMPLES {
+        let config: generated::Config = from_str(sample).unwrap();
+        let reparsed: generated::Config = from_str(&to_string(&config)).unwrap();
+        assert_eq!(reparsed, config);
+    }
+
+    let config: generated::Config = from_str(SAMPLES[1]).unwrap();
+    assert_eq!(config.status, generated::Status::Draining);
+    assert_eq!(config.mode, generated::Level::Low);
+    assert_eq!(config.replicas[0].weight, 0.5);
+    assert_eq!(config.replicas[0].zone, Some('a'));
+    assert_eq!(config.database.timeout, None);
+    assert_eq!(config.r#type, None);
+}
+
+#[test]
+fn test_generated_names_and_fallbacks() {
+    let rust = Schema::infer(&son!([{ id: 1 }])).to_rust("orders");
+    assert!(rust.contains("pub type Orders = Vec<Order>;"), "{}", rust);
+    assert!(rust.contains("pub struct Order {\n    pub id: i64,\n}"), "{}", rust);
+
+    let mut weird = std::collections::HashMap::new();
+    weird.insert("max connections".to_string(), Value::Integer(10));
+    let rust = Schema::infer(&son!({ limits: #(Value::Object(weird)) big: 170141183460469231731687303715884105727 }))
+        .to_rust("settings");
+    assert!(rust.contains("pub big: i128,"), "{}", rust);
+    assert!(
+        rust.contains("pub limits: std::collections::HashMap<String, Value>,"),
+        "{}",
+        rust
+    );
+
+    let rust = Schema::infer(&son!({ serverName: "a" mode: fast_path })).to_rust("Settings");
+    assert!(rust.contains("#[allow(non_snake_case)]"), "{}", rust);
+    assert!(rust.contains("#[allow(non_camel_case_types)]"), "{}", rust);
+}
+
+#[test]
+fn test_generated_enums_are_named_after_their_qualifier() {
+    let schema = Schema::infer_all(&[son!({ mode: Status.Active status: Draining }), son!({ mode: Idle })]);
+    let Schema::Object(fields) = &schema else {
+        panic!("Expected an object schema");
+    };
+    assert_eq!(
+        fields["mode"].schema,
+        Schema::Enum {
+            type_name: Some("Status".to_string()),
+            variants: BTreeSet::from(["Active".to_string(), "Idle".to_string()]),
+        }
+    );
+    let rust = schema.to_rust("Settings");
+    assert!(rust.contains("pub mode: Status,"), "{}", rust);
+    assert!(
+        rust.contains("pub enum Status {\n    Active,\n    Idle,\n}"),
+        "{}",
+        rust
+    );
+    assert!(rust.contains("pub status: Option<Status2>,"), "{}", rust);
+
+    // Fields qualified with the same type name share one enum.
+    let rust = Schema::infer(&son!({ from: Status.Active to: Status.Idle })).to_rust("Transition");
+    assert!(rust.contains("pub from: Status,\n    pub to: Status,"), "{}", rust);
+    assert!(
+        rust.contains("pub enum Status {\n    Active,\n    Idle,\n}"),
+        "{}",
+        rust
+    );
+
+    // Variants of different enums don't unify.
+    let schema = Schema::infer_all(&[son!({ mode: Status.Active }), son!({ mode: Mode.Active })]);
+    assert!(schema.to_rust("Settings").contains("pub mode: Value,"));
+}
@@ -0,0 +1,117 @@
+use son::{EnumVariant, Serialize, ToSon, Value, son};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+enum Status {
+    Active,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn test_son_macro_literals() {
+    assert_eq!(son!(null), Value::Null);
+    assert_eq!(son!(true), Value::Bool(true));
+    assert_eq!(son!(42), Value::Integer(42));
+    assert_eq!(son!(-42), Value::Integer(-42));
+    assert_eq!(
+        son!(-170141183460469231731687303715884105728),
+        Value::Integer(i128::MIN)
+    );
+    assert_eq!(son!(2.5), Value::Float(2.5));
+    assert_eq!(son!(-0.5), Value::Float(-0.5));
+    assert_eq!(son!("line\n\"quoted\""), Value::String("line\n\"quoted\"".to_string()));
+    assert_eq!(son!('A'), Value::Char('A'));
+    assert_eq!(son!(Active), Value::Enum("Active".into()));
+    assert_eq!(
+        son!(Status::Active),
+        Value::Enum(EnumVariant::qualified("Status", "Active"))
+    );
+    assert_eq!(
+        son!(accounts::Status::Active),
+        Value::Enum(EnumVariant::qualified("accounts::Status", "Active"))
+    );
+    assert_eq!(son!([]), Value::Array(Vec::new()));
+    assert_eq!(son!({}), Value::Object(HashMap::new()));
+    assert_eq!(son!(), Value::Object(HashMap::new()));
+}
+
+#[test]
+fn test_son_macro_matches_parser() {
+    let value = son! {
+        name: "Alice",
+        age: 30
+        // Comments and optional commas work like in SON files
+        tags: ["a" "b", "c"]
+        address {
+            city: "New York"
+            "zip code": "10021"
+        }
+        matrix [[1 2] [3 4]]
+        status: Active
+        nothing: null
+    };
+
+    let parsed = son::from_str::<Value>(
+        r#"{
+            name: "Alice"
+            age: 30
+            tags: ["a" "b" "c"]
+            address: { city: "New York" zip_code: "10021" }
+            matrix: [[1 2] [3 4]]
+            status: Active
+            nothing: null
+        }"#,
+    )
+    .unwrap();
+
+    let Value::Object(mut parsed) = parsed else {
+        panic!("Expected an object");
+    };
+    let Some(Value::Object(address)) = parsed.get_mut("address") else {
+        panic!("Expected an address object");
+    };
+    let zip = address.remove("zip_code").unwrap();
+    address.insert("zip code".to_string(), zip);
+
+    assert_eq!(value, Value::Object(parsed));
+}
+
+#[test]
+fn test_son_macro_interpolation() {
+    let port = 8080u16;
+    let server = Server {
+        host: "localhost".to_string(),
+        port,
+    };
+    let tags = vec!["web", "edge"];
+    let map = 1;
+
+    let value = son! {
+        port: #port
+        server: #server
+        host: #server.host
+        tags: #tags
+        status: #(Status::Active)
+        next_port: #(port as i32 + 1)
+        nested: { list: [#map #(map + 1)] }
+    };
+
+    assert_eq!(
+        value,
+        son! {
+            port: 8080
+            server: { host: "localhost" port: 8080 }
+            host: "localhost"
+            tags: ["web" "edge"]
+            status: Status.Active
+            next_port: 8081
+            nested: { list: [1 2] }
+        }
+    );
+    assert_eq!(son!(#server), server.to_son());
+}
@@ -0,0 +1,159 @@
+use son::{ByteSize, DeserializationError, Deserialize, FromSon, Serialize, ToSon, Value};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct PoolConfig {
+    timeout: Duration,
+    buffer: ByteSize,
+    max_body: ByteSize,
+}
+
+fn field(value: &Value, key: &str) -> Value {
+    return value.get_path(&son::Path::root().key(key)).unwrap().clone();
+}
+
+#[test]
+fn test_parse_duration_literals() {
+    let value = son::from_str::<Value>(
+        r#"{
+            short: 250ms
+            plain: 30s
+            compound: 1h30m
+            fractional: 1.5h
+            tiny: 10ns
+            micro: 15us
+            days: 2d
+            zero: 0s
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(field(&value, "short"), Value::Duration(Duration::from_millis(250)));
+    assert_eq!(field(&value, "plain"), Value::Duration(Duration::from_secs(30)));
+    assert_eq!(field(&value, "compound"), Value::Duration(Duration::from_secs(5400)));
+    assert_eq!(field(&value, "fractional"), Value::Duration(Duration::from_secs(5400)));
+    assert_eq!(field(&value, "tiny"), Value::Duration(Duration::from_nanos(10)));
+    assert_eq!(field(&value, "micro"), Value::Duration(Duration::from_micros(15)));
+    assert_eq!(field(&value, "days"), Value::Duration(Duration::from_secs(2 * 86400)));
+    assert_eq!(field(&value, "zero"), Value::Duration(Duration::ZERO));
+    assert_eq!(field(&value, "plain").get_type(), "Duration");
+}
+
+#[test]
+fn test_parse_size_literals() {
+    let value = son::from_str::<Value>("[64KiB 2GB 512B 1.5MiB 0.5KB 3TiB]").unwrap();
+    let expected = [65_536, 2_000_000_000, 512, 1_572_864, 500, 3 << 40];
+    assert_eq!(
+        value,
+        Value::Array(expected.iter().map(|b| Value::ByteSize(ByteSize::new(*b))).collect())
+    );
+    assert_eq!("64KiB".parse::<ByteSize>(), Ok(ByteSize::kib(64)));
+    assert!("30s".parse::<ByteSize>().is_err());
+}
+
+#[test]
+fn test_invalid_unit_literals() {
+    let invalid = [
+        "30sec",
+        "10x",
+        "5kb",
+        "1.5B",
+        "1.0000000001s",
+        "1h30",
+        "99999999999999999999PiB",
+        "1..5s",
+    ];
+    for literal in invalid {
+        let result = son::from_str::<Value>(&format!("{{ a: {} }}", literal));
+        assert!(result.is_err(), "{} should not parse", literal);
+    }
+
+    let error = son::from_str::<Value>("{ timeout: 30sec }").unwrap_err().to_string();
+    assert!(error.contains("unknown unit 'sec'"), "{}", error);
+
+    // Numbers without a unit are unaffected.
+    assert_eq!(son::from_str::<Value>("[30 1.5]").unwrap().get_type(), "Array");
+}
+
+#[test]
+fn test_negative_literals_must_be_numbers() {
+    for literal in [
+        "-30s",
+        "-1h30m",
+        "-64KiB",
+        "-2024-01-15",
+        "-\"text\"",
+        "-'c'",
+        "-Active",
+        "-[1]",
+        "-{}",
+        "-null",
+    ] {
+        let source = format!("{{ a: {} }}", literal);
+        let error = son::from_str::<Value>(&source).unwrap_err().to_string();
+        assert!(error.contains("Unexpected token"), "{}: {}", literal, error);
+        assert!(son::from_str_borrowed(&source).is_err(), "{} should not parse", literal);
+    }
+
+    let value = son::from_str::<Value>("[-30 -1.5 --2]").unwrap();
+    assert_eq!(
+        value,
+        Value::Array(vec![Value::Integer(-30), Value::Float(-1.5), Value::Integer(2)])
+    );
+    assert_eq!(son::from_str_borrowed("[-30 -1.5 --2]").unwrap().into_owned(), value);
+}
+
+#[test]
+fn test_units_print_and_round_trip() {
+    let durations = [
+        (Duration::from_secs(5400), "1h30m"),
+        (Duration::from_millis(1500), "1s500ms"),
+        (Duration::from_micros(15), "15us"),
+        (Duration::from_nanos(3_000_000_001), "3s1ns"),
+        (Duration::ZERO, "0s"),
+    ];
+    for (duration, printed) in durations {
+        assert_eq!(son::units::format_duration(&duration), printed);
+        assert_eq!(son::units::parse_duration(printed), Ok(duration));
+    }
+
+    let sizes = [
+        (65_536, "64KiB"),
+        (2_000_000_000, "2GB"),
+        (1000, "1KB"),
+        (1023, "1023B"),
+        (0, "0B"),
+    ];
+    for (bytes, printed) in sizes {
+        assert_eq!(ByteSize::new(bytes).to_string(), printed);
+    }
+
+    let value = Value::Array(vec![
+        Value::Duration(Duration::from_millis(1500)),
+        Value::ByteSize(ByteSize::mib(8)),
+    ]);
+    let reparsed = son::from_str::<Value>(&value.to_string()).unwrap();
+    assert_eq!(reparsed, value);
+}
+
+#[test]
+fn test_serialize_and_deserialize_units() {
+    let config: PoolConfig = son::from_str("{ timeout: 30s buffer: 64KiB max_body: 1048576 }").unwrap();
+    assert_eq!(
+        config,
+        PoolConfig {
+            timeout: Duration::from_secs(30),
+            buffer: ByteSize::kib(64),
+            max_body: ByteSize::mib(1),
+        }
+    );
+
+    let printed = son::to_string(&config);
+    assert!(printed.contains("timeout:30s"));
+    assert!(printed.contains("max_body:1MiB"));
+    assert_eq!(son::from_str::<PoolConfig>(&printed).unwrap(), config);
+
+    // Bare numbers are ambiguous for durations and are rejected.
+    assert!(son::from_str::<PoolConfig>("{ timeout: 30 buffer: 1 max_body: 1 }").is_err());
+    assert!(ByteSize::from_son(Value::Integer(-1)).is_err());
+}
@@ -0,0 +1,145 @@
+use son::{Path, Value, Walk, son};
+
+fn document() -> Value {
+    return son! {
+        service: "billing"
+        status: Active
+        database: { user: "admin" password: "hunter2" port: 5432 }
+        replicas: [
+            { region: EuWest password: "s3cret" }
+            { region: UsEast }
+        ]
+    };
+}
+
+#[test]
+fn test_walk_visits_in_order_with_paths() {
+    let mut visited = Vec::new();
+    let completed = document().walk(|path, value| {
+        visited.push(format!("{} {}", path, value.get_type()));
+        return Walk::Continue;
+    });
+
+    assert!(completed);
+    assert_eq!(
+        visited,
+        [
+            "$ Object",
+            "$.database Object",
+            "$.database.password String",
+            "$.database.port Integer",
+            "$.database.user String",
+            "$.replicas Array",
+            "$.replicas[0] Object",
+            "$.replicas[0].password String",
+            "$.replicas[0].region Enum",
+            "$.replicas[1] Object",
+            "$.replicas[1].region Enum",
+            "$.service String",
+            "$.status Enum",
+        ]
+    );
+}
+
+#[test]
+fn test_walk_early_termination() {
+    let mut visited = Vec::new();
+    let completed = document().walk(|path, _| {
+        visited.push(path.to_string());
+        if path.to_string() == "$.database" {
+            return Walk::SkipChildren;
+        }
+        if path.to_string() == "$.replicas[0]" {
+            return Walk::Stop;
+        }
+        return Walk::Continue;
+    });
+
+    assert!(!completed);
+    assert_eq!(visited, ["$", "$.database", "$.replicas", "$.replicas[0]"]);
+}
+
+#[test]
+fn test_walk_mut_redacts_secrets() {
+    let mut value = document();
+    let completed = value.walk_mut(|path, value| {
+        if let Some(son::PathSegment::Key(key)) = path.last()
+            && key == "password"
+        {
+            *value = Value::String("***".to_string());
+        }
+        return Walk::Continue;
+    });
+
+    assert!(completed);
+    let passwords: Vec<&Value> = value
+        .query("$..password")
+        .unwrap()
+        .into_iter()
+        .map(|m| m.value)
+        .collect();
+    assert_eq!(
+        passwords,
+        [&Value::String("***".to_string()), &Value::String("***".to_string())]
+    );
+    assert_eq!(
+        value.query("$.database.user").unwrap()[0].value,
+        &Value::String("admin".to_string())
+    );
+}
+
+#[test]
+fn test_map_leaves_and_try_map() {
+    let upper = document().map_leaves(|_, leaf| {
+        return match leaf {
+            Value::String(s) => Value::String(s.to_uppercase()),
+            other => other,
+        };
+    });
+    assert_eq!(
+        upper.query("$.service").unwrap()[0].value,
+        &Value::String("BILLING".to_string())
+    );
+    assert_eq!(upper.query("$.database.port").unwrap()[0].value, &Value::Integer(5432));
+
+    let doubled = son!([1 [2 3]]).try_map(|_, leaf| match leaf {
+        Value::Integer(i) => Ok(Value::Integer(i * 2)),
+        _ => Err("not an integer"),
+    });
+    assert_eq!(doubled, Ok(son!([2 [4 6]])));
+
+    let mut seen = Vec::new();
+    let failed = son!([1 "two" 3]).try_map(|path, leaf| {
+        seen.push(path.to_string());
+        return match leaf {
+            Value::Integer(i) => Ok(Value::Integer(i)),
+            _ => Err(format!("{} is not an integer", path)),
+        };
+    });
+    assert_eq!(failed, Err("$[1] is not an integer".to_string()));
+    assert_eq!(seen, ["$[0]", "$[1]"]);
+}
+
+#[test]
+fn test_fold_and_iter_paths() {
+    let value = document();
+    let enums = value.fold(Vec::new(), |mut enums, _, value| {
+        if let Value::Enum(name) = value {
+            enums.push(name.clone());
+        }
+        return enums;
+    });
+    assert_eq!(enums, ["EuWest", "UsEast", "Active"]);
+
+    let from_walk = value.fold(Vec::new(), |mut paths, path, _| {
+        paths.push(path.clone());
+        return paths;
+    });
+    let from_iter: Vec<Path> = value.iter_paths().map(|(path, _)| path).collect();
+    assert_eq!(from_iter, from_walk);
+
+    let (path, port) = value.iter_paths().find(|(_, v)| **v == Value::Integer(5432)).unwrap();
+    assert_eq!(path.to_string(), "$.database.port");
+    assert_eq!(value.get_path(&path), Some(port));
+    assert_eq!(Value::Null.iter_paths().count(), 1);
+}
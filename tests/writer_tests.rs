@@ -0,0 +1,125 @@
+use son::{
+    DeserializationError, Deserialize, Error, FromSon, PrinterOptions, Serialize, ToSon, Value, from_file, from_str,
+    printer::Printer, son, to_string, to_string_pretty, to_string_with, to_writer, to_writer_pretty, to_writer_with,
+};
+use std::io::{self, Write};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Reading {
+    sensor: String,
+    values: Vec<f64>,
+}
+
+// Counts the writes that reach the underlying writer, and fails once `limit` bytes were written.
+struct Sink {
+    written: Vec<u8>,
+    writes: usize,
+    limit: usize,
+}
+
+impl Sink {
+    fn new(limit: usize) -> Sink {
+        return Sink {
+            written: Vec::new(),
+            writes: 0,
+            limit,
+        };
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written.len() + buf.len() > self.limit {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "disk full"));
+        }
+        self.writes += 1;
+        self.written.extend_from_slice(buf);
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return Ok(());
+    }
+}
+
+fn readings(count: usize) -> Vec<Reading> {
+    return (0..count)
+        .map(|i| Reading {
+            sensor: format!("sensor-{}", i),
+            values: vec![i as f64, i as f64 + 0.5],
+        })
+        .collect();
+}
+
+#[test]
+fn test_to_writer_matches_to_string() {
+    let value = son!({ name: "api" ports: [80 443] limits: { cpu: 2 } });
+
+    let mut output = Vec::new();
+    to_writer(&mut output, &value).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), to_string(&value));
+
+    let mut output = Vec::new();
+    to_writer_pretty(&mut output, &value, "  ").unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), to_string_pretty(&value, "  "));
+
+    let options = PrinterOptions::compact().sort_keys(true).final_newline(true);
+    let mut output = Vec::new();
+    to_writer_with(&mut output, &value, &options).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), to_string_with(&value, &options));
+}
+
+#[test]
+fn test_write_to_file() {
+    let path = std::env::temp_dir().join(format!("son-writer-test-{}.son", std::process::id()));
+    let data = readings(3);
+
+    let file = std::fs::File::create(&path).unwrap();
+    to_writer_pretty(file, &data, "    ").unwrap();
+    let read: Vec<Reading> = from_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read, data);
+}
+
+#[test]
+fn test_io_errors_are_returned() {
+    let mut sink = Sink::new(100);
+    let result = to_writer(&mut sink, &readings(100));
+    let Err(Error::IOError(error)) = result else {
+        panic!("Expected an IO error");
+    };
+    assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+
+    let mut sink = Sink::new(100);
+    let printer = Printer::with_options(PrinterOptions::new());
+    assert!(printer.write_value(&mut sink, &son!([1 2 3])).is_ok());
+    assert_eq!(sink.written, b"[1 2 3]\n");
+}
+
+#[test]
+fn test_output_is_buffered() {
+    let data = readings(10_000);
+    let mut sink = Sink::new(usize::MAX);
+    to_writer(&mut sink, &data).unwrap();
+
+    // Printing makes several small writes per reading, which reach the sink in a few large chunks.
+    assert!(sink.writes < sink.written.len() / 1000, "{} writes", sink.writes);
+    assert_eq!(
+        from_str::<Vec<Reading>>(std::str::from_utf8(&sink.written).unwrap()).unwrap(),
+        data
+    );
+}
+
+#[test]
+fn test_writer_can_be_reused() {
+    let mut output = Vec::new();
+    to_writer(&mut output, &son!([1])).unwrap();
+    output.push(b'\n');
+    to_writer(&mut output, &son!({ a: true })).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "[1]\n{a:true}");
+
+    let value: Value = from_str("{ a: [1 2] }").unwrap();
+    let mut output = Vec::new();
+    to_writer(&mut output, &value).unwrap();
+    assert_eq!(from_str::<Value>(std::str::from_utf8(&output).unwrap()).unwrap(), value);
+}